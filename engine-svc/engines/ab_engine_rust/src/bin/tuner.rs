@@ -0,0 +1,135 @@
+// ab_engine_rust/src/bin/tuner.rs
+//
+// Texel-style tuning: reads `<fen> <result>` lines (result in {0, 0.5, 1}, White's point
+// of view), fits the logistic scale factor `K` with a 1-D scan, then coordinate-descends
+// over the full `EvalParams` vector (try +1 then -1 on each weight, keep whichever drops
+// the error) until a full pass makes no further improvement.
+//
+// Usage: `tuner <positions-file> [out-params-file]`
+
+use chess::Board;
+use engine::eval::ClassicalEval;
+use engine::evalparams::EvalParams;
+use std::env;
+use std::fs;
+use std::str::FromStr;
+
+struct Sample {
+    board: Board,
+    result: f64,
+}
+
+fn load_samples(path: &str) -> Vec<Sample> {
+    let text = fs::read_to_string(path).unwrap_or_else(|e| panic!("reading {}: {}", path, e));
+    let mut samples = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') { continue; }
+
+        let mut tokens: Vec<&str> = line.split_whitespace().collect();
+        let Some(result_tok) = tokens.pop() else { continue; };
+        let Ok(result) = result_tok.parse::<f64>() else { continue; };
+        let fen = tokens.join(" ");
+
+        if let Ok(board) = Board::from_str(&fen) {
+            samples.push(Sample { board, result });
+        }
+    }
+    samples
+}
+
+/// `ClassicalEval::eval` is relative to the side to move; Texel's method wants every
+/// position scored from the same (White's) point of view so it lines up with `result`.
+fn white_eval(eval: &ClassicalEval, s: &Sample) -> f64 {
+    let e = eval.eval(&s.board) as f64;
+    if s.board.side_to_move() == chess::Color::White { e } else { -e }
+}
+
+#[inline]
+fn sigmoid(k: f64, s: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-k * s / 400.0))
+}
+
+fn mean_squared_error(samples: &[Sample], evals: &[f64], k: f64) -> f64 {
+    let sum: f64 = samples
+        .iter()
+        .zip(evals.iter())
+        .map(|(s, &e)| {
+            let d = s.result - sigmoid(k, e);
+            d * d
+        })
+        .sum();
+    sum / samples.len().max(1) as f64
+}
+
+/// Coarse 1-D scan for the `K` that best maps the static eval onto the logistic curve.
+/// Done once, before tuning the weights themselves, exactly as Texel's method prescribes.
+fn fit_k(samples: &[Sample], evals: &[f64]) -> f64 {
+    let mut best_k = 0.0;
+    let mut best_err = f64::INFINITY;
+    let mut k = 0.0;
+    while k <= 2.0 {
+        let err = mean_squared_error(samples, evals, k);
+        if err < best_err {
+            best_err = err;
+            best_k = k;
+        }
+        k += 0.01;
+    }
+    best_k
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        eprintln!("usage: tuner <positions-file> [out-params-file]");
+        std::process::exit(1);
+    }
+
+    let samples = load_samples(&args[1]);
+    if samples.is_empty() {
+        eprintln!("no usable positions in {}", args[1]);
+        std::process::exit(1);
+    }
+    println!("loaded {} positions", samples.len());
+
+    let mut vec = EvalParams::default().to_vec();
+
+    let initial_eval = ClassicalEval::new(EvalParams::from_vec(&vec));
+    let initial_evals: Vec<f64> = samples.iter().map(|s| white_eval(&initial_eval, s)).collect();
+    let k = fit_k(&samples, &initial_evals);
+    println!("fit K = {:.4}", k);
+
+    let mut best_err = mean_squared_error(&samples, &initial_evals, k);
+    println!("initial error = {:.6}", best_err);
+
+    loop {
+        let mut improved = false;
+        for i in 0..vec.len() {
+            let original = vec[i];
+            for delta in [1i32, -1i32] {
+                vec[i] = original + delta;
+                let eval = ClassicalEval::new(EvalParams::from_vec(&vec));
+                let evals: Vec<f64> = samples.iter().map(|s| white_eval(&eval, s)).collect();
+                let err = mean_squared_error(&samples, &evals, k);
+                if err < best_err {
+                    best_err = err;
+                    improved = true;
+                    break;
+                }
+                vec[i] = original;
+            }
+        }
+        println!("pass done, error = {:.6}", best_err);
+        if !improved { break; }
+    }
+
+    let tuned = EvalParams::from_vec(&vec).to_kv_string();
+    match args.get(2) {
+        Some(out_path) => {
+            fs::write(out_path, &tuned).unwrap_or_else(|e| panic!("writing {}: {}", out_path, e));
+            println!("wrote tuned params to {}", out_path);
+        }
+        None => println!("{}", tuned),
+    }
+}
@@ -1,11 +1,20 @@
 use chess::{Board, BoardStatus, Color, Piece, Square, BitBoard};
+use crate::evalparams::EvalParams;
 use crate::types::*;
 
-pub struct ClassicalEval;
+#[derive(Default)]
+pub struct ClassicalEval {
+    pub params: EvalParams,
+}
 
 impl ClassicalEval {
+    pub fn new(params: EvalParams) -> Self {
+        Self { params }
+    }
+
     #[inline]
     pub fn eval(&self, b: &Board) -> i32 {
+        let pm = &self.params;
         match b.status() {
             BoardStatus::Checkmate => return -MATE,
             BoardStatus::Stalemate => return 0,
@@ -20,11 +29,11 @@ impl ClassicalEval {
         // ----- Material base (kept) -----
         for &color in &[Color::White, Color::Black] {
             let sgn = if color == b.side_to_move() { 1 } else { -1 };
-            let mat = P * count_pieces(b, Piece::Pawn, color)
-                    + N * count_pieces(b, Piece::Knight, color)
-                    + B * count_pieces(b, Piece::Bishop, color)
-                    + R_ * count_pieces(b, Piece::Rook,   color)
-                    + Q_ * count_pieces(b, Piece::Queen,  color);
+            let mat = pm.p * count_pieces(b, Piece::Pawn, color)
+                    + pm.n * count_pieces(b, Piece::Knight, color)
+                    + pm.b * count_pieces(b, Piece::Bishop, color)
+                    + pm.r * count_pieces(b, Piece::Rook,   color)
+                    + pm.q * count_pieces(b, Piece::Queen,  color);
             mg += sgn * mat;
             eg += sgn * mat;
         }
@@ -34,23 +43,23 @@ impl ClassicalEval {
         let fm = fullmove_number_from_fen(b);
 
         // tempo (small and only MG)
-        mg += TEMPO_BONUS;
+        mg += pm.tempo_bonus;
 
         for &color in &[Color::White, Color::Black] {
             let sgn = if color == b.side_to_move() { 1 } else { -1 };
 
             // bishop pair
             if count_pieces(b, Piece::Bishop, color) >= 2 {
-                mg += sgn * BISHOP_PAIR_MG;
-                eg += sgn * BISHOP_PAIR_EG;
+                mg += sgn * pm.bishop_pair_mg;
+                eg += sgn * pm.bishop_pair_eg;
             }
 
             // castling encouragement in opening
             if opening_like {
                 if is_castled(b, color) {
-                    mg += sgn * CASTLED_BONUS_EARLY;
+                    mg += sgn * pm.castled_bonus_early;
                 } else if fm >= 10 {
-                    mg -= sgn * UNCASTLED_PENALTY_EARLY;
+                    mg -= sgn * pm.uncastled_penalty_early;
                 }
             }
 
@@ -61,24 +70,24 @@ impl ClassicalEval {
                 let f = file_idx(ps);
                 if (color == Color::White && rrel == 3 && (f == 3 || f == 4)) ||
                    (color == Color::Black && rrel == 4 && (f == 3 || f == 4)) {
-                    mg += sgn * CENTER_PAWN_BONUS;
+                    mg += sgn * pm.center_pawn_bonus;
                 }
             }
 
             // rook on (semi) open file (MG)
             let rooks = b.color_combined(color) & b.pieces(Piece::Rook);
             for rsq in rooks {
-                mg += sgn * rook_file_bonus(b, color, rsq);
+                mg += sgn * rook_file_bonus(b, color, rsq, pm.rook_open_file_bonus, pm.rook_semiopen_file_bonus);
             }
 
             // light pawn-structure penalties in MG
             for ps in pawns {
                 let f = file_idx(ps);
                 if is_doubled_pawn_on_file(b, color, f) {
-                    mg -= sgn * DOUBLED_PAWN_PENALTY_MG;
+                    mg -= sgn * pm.doubled_pawn_penalty_mg;
                 }
                 if is_isolated_pawn(b, color, f) {
-                    mg -= sgn * ISOLATED_PAWN_PENALTY_MG;
+                    mg -= sgn * pm.isolated_pawn_penalty_mg;
                 }
             }
 
@@ -97,22 +106,27 @@ impl ClassicalEval {
                         | BitBoard::from_square(Square::F8)
                 };
                 let stuck = (minors & home).popcnt() as i32;
-                mg -= sgn * MINOR_DEV_PENALTY * stuck;
+                mg -= sgn * pm.minor_dev_penalty * stuck;
             }
         }
 
-        // ----- Endgame (kept from previous step) -----
-        // King EG centralization
+        // ----- Piece-square tables (tapered MG/EG) -----
+        const PST_PIECES: [Piece; 6] = [
+            Piece::Pawn, Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen, Piece::King,
+        ];
         for &color in &[Color::White, Color::Black] {
             let sgn = if color == b.side_to_move() { 1 } else { -1 };
-            let bb = b.color_combined(color) & b.pieces(Piece::King);
-            if bb.popcnt() >= 1 {
-                let sq: Square = bb.to_square();
-                let idx = pst_index_for(color, sq);
-                eg += sgn * PST_KING_EG[idx];
+            for &piece in &PST_PIECES {
+                let bb = b.color_combined(color) & b.pieces(piece);
+                for sq in bb {
+                    let idx = pst_index_for(color, sq);
+                    mg += sgn * pm.pst_mg(piece, idx);
+                    eg += sgn * pm.pst_eg(piece, idx);
+                }
             }
         }
 
+        // ----- Endgame (kept from previous step) -----
         // Passed pawns
         for &color in &[Color::White, Color::Black] {
             let sgn = if color == b.side_to_move() { 1 } else { -1 };
@@ -120,7 +134,7 @@ impl ClassicalEval {
             for sq in pawns {
                 if is_passed_pawn(b, sq, color) {
                     let rr = relative_rank(color, sq);
-                    eg += sgn * PASSED_PAWN_BONUS_BY_RANK[rr];
+                    eg += sgn * pm.passed_pawn_bonus_by_rank[rr];
                 }
             }
         }
@@ -139,15 +153,14 @@ impl ClassicalEval {
 
             for sq in rooks {
                 if relative_rank(color, sq) == 6 && (opp_has_pawns || opp_king_backrank) {
-                    eg += sgn * 18;
+                    eg += sgn * pm.rook_on_7th_eg;
                 }
                 let f = file_idx(sq);
                 let pawns = b.color_combined(color) & b.pieces(Piece::Pawn);
                 for ps in pawns {
-                    if file_idx(ps) == f && is_passed_pawn(b, ps, color) {
-                        if relative_rank(color, sq) < relative_rank(color, ps) {
-                            eg += sgn * 20;
-                        }
+                    if file_idx(ps) == f && is_passed_pawn(b, ps, color)
+                        && relative_rank(color, sq) < relative_rank(color, ps) {
+                        eg += sgn * pm.rook_behind_passer_eg;
                     }
                 }
             }
@@ -167,7 +180,7 @@ impl ClassicalEval {
         {
             let wb = (b.color_combined(Color::White) & b.pieces(Piece::Bishop)).to_square();
             let bb = (b.color_combined(Color::Black) & b.pieces(Piece::Bishop)).to_square();
-            let is_light = |sq: Square| -> bool { let i = sq.to_index(); ((i % 8) + (i / 8)) % 2 == 0 };
+            let is_light = |sq: Square| -> bool { let i = sq.to_index(); ((i % 8) + (i / 8)).is_multiple_of(2) };
             if is_light(wb) != is_light(bb) {
                 mg = mg * 3 / 4;
                 eg = eg * 3 / 4;
@@ -0,0 +1,288 @@
+// ab_engine_rust/src/evalparams.rs
+//
+// Every weight `ClassicalEval` scores with, bundled into one struct so it can be loaded
+// from a file (via `EVAL_PARAMS_PATH`/`setoption name EvalParamsFile`) instead of baked
+// in at compile time. `default()` reproduces the constants `ClassicalEval` used to read
+// straight from `types.rs`, so nothing changes unless a params file is supplied.
+//
+// The `tuner` binary treats an `EvalParams` as a flat `Vec<i32>` via `to_vec`/`from_vec`
+// and coordinate-descends over it; the field order only has to be self-consistent
+// between the two, not meaningful to a reader.
+
+use chess::Piece;
+
+use crate::types::{
+    B, BISHOP_PAIR_EG, BISHOP_PAIR_MG, CASTLED_BONUS_EARLY, CENTER_PAWN_BONUS,
+    DOUBLED_PAWN_PENALTY_MG, ISOLATED_PAWN_PENALTY_MG, MINOR_DEV_PENALTY, N,
+    PASSED_PAWN_BONUS_BY_RANK, P, PST_BISHOP, PST_BISHOP_EG, PST_KING, PST_KING_EG, PST_KNIGHT,
+    PST_KNIGHT_EG, PST_PAWN, PST_PAWN_EG, PST_QUEEN, PST_QUEEN_EG, PST_ROOK, PST_ROOK_EG, Q_,
+    ROOK_OPEN_FILE_BONUS, ROOK_SEMIOPEN_FILE_BONUS, R_, TEMPO_BONUS, UNCASTLED_PENALTY_EARLY,
+};
+
+#[derive(Clone, Debug)]
+pub struct EvalParams {
+    pub p: i32,
+    pub n: i32,
+    pub b: i32,
+    pub r: i32,
+    pub q: i32,
+
+    pub tempo_bonus: i32,
+    pub bishop_pair_mg: i32,
+    pub bishop_pair_eg: i32,
+    pub castled_bonus_early: i32,
+    pub uncastled_penalty_early: i32,
+    pub center_pawn_bonus: i32,
+    pub minor_dev_penalty: i32,
+    pub rook_open_file_bonus: i32,
+    pub rook_semiopen_file_bonus: i32,
+    pub doubled_pawn_penalty_mg: i32,
+    pub isolated_pawn_penalty_mg: i32,
+    pub rook_on_7th_eg: i32,
+    pub rook_behind_passer_eg: i32,
+
+    pub passed_pawn_bonus_by_rank: [i32; 8],
+
+    pub pst_pawn: [i32; 64],
+    pub pst_knight: [i32; 64],
+    pub pst_bishop: [i32; 64],
+    pub pst_rook: [i32; 64],
+    pub pst_queen: [i32; 64],
+    pub pst_king: [i32; 64],
+
+    pub pst_pawn_eg: [i32; 64],
+    pub pst_knight_eg: [i32; 64],
+    pub pst_bishop_eg: [i32; 64],
+    pub pst_rook_eg: [i32; 64],
+    pub pst_queen_eg: [i32; 64],
+    pub pst_king_eg: [i32; 64],
+}
+
+impl Default for EvalParams {
+    fn default() -> Self {
+        Self {
+            p: P,
+            n: N,
+            b: B,
+            r: R_,
+            q: Q_,
+
+            tempo_bonus: TEMPO_BONUS,
+            bishop_pair_mg: BISHOP_PAIR_MG,
+            bishop_pair_eg: BISHOP_PAIR_EG,
+            castled_bonus_early: CASTLED_BONUS_EARLY,
+            uncastled_penalty_early: UNCASTLED_PENALTY_EARLY,
+            center_pawn_bonus: CENTER_PAWN_BONUS,
+            minor_dev_penalty: MINOR_DEV_PENALTY,
+            rook_open_file_bonus: ROOK_OPEN_FILE_BONUS,
+            rook_semiopen_file_bonus: ROOK_SEMIOPEN_FILE_BONUS,
+            doubled_pawn_penalty_mg: DOUBLED_PAWN_PENALTY_MG,
+            isolated_pawn_penalty_mg: ISOLATED_PAWN_PENALTY_MG,
+            rook_on_7th_eg: 18,
+            rook_behind_passer_eg: 20,
+
+            passed_pawn_bonus_by_rank: PASSED_PAWN_BONUS_BY_RANK,
+
+            pst_pawn: PST_PAWN,
+            pst_knight: PST_KNIGHT,
+            pst_bishop: PST_BISHOP,
+            pst_rook: PST_ROOK,
+            pst_queen: PST_QUEEN,
+            pst_king: PST_KING,
+
+            pst_pawn_eg: PST_PAWN_EG,
+            pst_knight_eg: PST_KNIGHT_EG,
+            pst_bishop_eg: PST_BISHOP_EG,
+            pst_rook_eg: PST_ROOK_EG,
+            pst_queen_eg: PST_QUEEN_EG,
+            pst_king_eg: PST_KING_EG,
+        }
+    }
+}
+
+/// Names paired 1:1 with the field order `to_vec`/`from_vec` use, for `key=value` writing
+/// (`to_kv_string`) and loading (`load_str`).
+const SCALAR_NAMES: [&str; 18] = [
+    "P", "N", "B", "R", "Q",
+    "TEMPO_BONUS", "BISHOP_PAIR_MG", "BISHOP_PAIR_EG",
+    "CASTLED_BONUS_EARLY", "UNCASTLED_PENALTY_EARLY", "CENTER_PAWN_BONUS",
+    "MINOR_DEV_PENALTY", "ROOK_OPEN_FILE_BONUS", "ROOK_SEMIOPEN_FILE_BONUS",
+    "DOUBLED_PAWN_PENALTY_MG", "ISOLATED_PAWN_PENALTY_MG",
+    "ROOK_ON_7TH_EG", "ROOK_BEHIND_PASSER_EG",
+];
+
+const ARRAY_NAMES: [&str; 13] = [
+    "PASSED_PAWN_BONUS_BY_RANK",
+    "PST_PAWN", "PST_KNIGHT", "PST_BISHOP", "PST_ROOK", "PST_QUEEN", "PST_KING",
+    "PST_PAWN_EG", "PST_KNIGHT_EG", "PST_BISHOP_EG", "PST_ROOK_EG", "PST_QUEEN_EG", "PST_KING_EG",
+];
+
+impl EvalParams {
+    pub fn scalars(&self) -> [i32; 18] {
+        [
+            self.p, self.n, self.b, self.r, self.q,
+            self.tempo_bonus, self.bishop_pair_mg, self.bishop_pair_eg,
+            self.castled_bonus_early, self.uncastled_penalty_early, self.center_pawn_bonus,
+            self.minor_dev_penalty, self.rook_open_file_bonus, self.rook_semiopen_file_bonus,
+            self.doubled_pawn_penalty_mg, self.isolated_pawn_penalty_mg,
+            self.rook_on_7th_eg, self.rook_behind_passer_eg,
+        ]
+    }
+
+    fn set_scalar(&mut self, i: usize, v: i32) {
+        match i {
+            0 => self.p = v, 1 => self.n = v, 2 => self.b = v, 3 => self.r = v, 4 => self.q = v,
+            5 => self.tempo_bonus = v, 6 => self.bishop_pair_mg = v, 7 => self.bishop_pair_eg = v,
+            8 => self.castled_bonus_early = v, 9 => self.uncastled_penalty_early = v,
+            10 => self.center_pawn_bonus = v, 11 => self.minor_dev_penalty = v,
+            12 => self.rook_open_file_bonus = v, 13 => self.rook_semiopen_file_bonus = v,
+            14 => self.doubled_pawn_penalty_mg = v, 15 => self.isolated_pawn_penalty_mg = v,
+            16 => self.rook_on_7th_eg = v, 17 => self.rook_behind_passer_eg = v,
+            _ => {}
+        }
+    }
+
+    fn array_mut(&mut self, i: usize) -> &mut [i32] {
+        match i {
+            0 => &mut self.passed_pawn_bonus_by_rank,
+            1 => &mut self.pst_pawn,
+            2 => &mut self.pst_knight,
+            3 => &mut self.pst_bishop,
+            4 => &mut self.pst_rook,
+            5 => &mut self.pst_queen,
+            6 => &mut self.pst_king,
+            7 => &mut self.pst_pawn_eg,
+            8 => &mut self.pst_knight_eg,
+            9 => &mut self.pst_bishop_eg,
+            10 => &mut self.pst_rook_eg,
+            11 => &mut self.pst_queen_eg,
+            12 => &mut self.pst_king_eg,
+            _ => unreachable!(),
+        }
+    }
+
+    fn array(&self, i: usize) -> &[i32] {
+        match i {
+            0 => &self.passed_pawn_bonus_by_rank,
+            1 => &self.pst_pawn,
+            2 => &self.pst_knight,
+            3 => &self.pst_bishop,
+            4 => &self.pst_rook,
+            5 => &self.pst_queen,
+            6 => &self.pst_king,
+            7 => &self.pst_pawn_eg,
+            8 => &self.pst_knight_eg,
+            9 => &self.pst_bishop_eg,
+            10 => &self.pst_rook_eg,
+            11 => &self.pst_queen_eg,
+            12 => &self.pst_king_eg,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Tapered PST lookup: the MG table's value for `piece` at `idx` (already mirrored by
+    /// `pst_index_for`).
+    pub fn pst_mg(&self, piece: Piece, idx: usize) -> i32 {
+        match piece {
+            Piece::Pawn => self.pst_pawn[idx],
+            Piece::Knight => self.pst_knight[idx],
+            Piece::Bishop => self.pst_bishop[idx],
+            Piece::Rook => self.pst_rook[idx],
+            Piece::Queen => self.pst_queen[idx],
+            Piece::King => self.pst_king[idx],
+        }
+    }
+
+    /// Tapered PST lookup: the EG table's value for `piece` at `idx`.
+    pub fn pst_eg(&self, piece: Piece, idx: usize) -> i32 {
+        match piece {
+            Piece::Pawn => self.pst_pawn_eg[idx],
+            Piece::Knight => self.pst_knight_eg[idx],
+            Piece::Bishop => self.pst_bishop_eg[idx],
+            Piece::Rook => self.pst_rook_eg[idx],
+            Piece::Queen => self.pst_queen_eg[idx],
+            Piece::King => self.pst_king_eg[idx],
+        }
+    }
+
+    /// The full parameter vector the tuner coordinate-descends over: scalars first, then
+    /// each array in `ARRAY_NAMES` order.
+    pub fn to_vec(&self) -> Vec<i32> {
+        let mut v = self.scalars().to_vec();
+        for i in 0..ARRAY_NAMES.len() { v.extend_from_slice(self.array(i)); }
+        v
+    }
+
+    pub fn len() -> usize {
+        SCALAR_NAMES.len() + ARRAY_NAMES.len() * 64 - 56 // PASSED_PAWN_BONUS_BY_RANK has 8, not 64
+    }
+
+    /// Inverse of `to_vec`. Panics if `v.len() != EvalParams::len()` -- the tuner always
+    /// round-trips through `to_vec`, so a mismatch means a field was added to one but not
+    /// the other.
+    pub fn from_vec(v: &[i32]) -> Self {
+        assert_eq!(v.len(), Self::len(), "EvalParams::from_vec: wrong vector length");
+        let mut p = EvalParams::default();
+        let mut idx = 0;
+        for i in 0..SCALAR_NAMES.len() { p.set_scalar(i, v[idx]); idx += 1; }
+        for i in 0..ARRAY_NAMES.len() {
+            let n = if i == 0 { 8 } else { 64 };
+            p.array_mut(i).copy_from_slice(&v[idx..idx + n]);
+            idx += n;
+        }
+        p
+    }
+
+    /// Renders as `NAME=value` (arrays as whitespace-separated values on one line), the
+    /// format `load_str` reads back.
+    pub fn to_kv_string(&self) -> String {
+        let mut out = String::new();
+        for (name, val) in SCALAR_NAMES.iter().zip(self.scalars().iter()) {
+            out.push_str(&format!("{}={}\n", name, val));
+        }
+        for (i, name) in ARRAY_NAMES.iter().enumerate() {
+            let vals: Vec<String> = self.array(i).iter().map(|x| x.to_string()).collect();
+            out.push_str(&format!("{}={}\n", name, vals.join(" ")));
+        }
+        out
+    }
+
+    /// Parses the `NAME=value` format `to_kv_string` writes. Unknown names and malformed
+    /// lines are skipped rather than treated as a hard error, matching how
+    /// `EngineOptions::apply` treats unrecognized UCI options.
+    pub fn load_str(s: &str) -> Self {
+        let mut params = EvalParams::default();
+        for line in s.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue; }
+            let Some((name, value)) = line.split_once('=') else { continue; };
+            let name = name.trim();
+            let value = value.trim();
+
+            if let Some(i) = SCALAR_NAMES.iter().position(|&n| n == name) {
+                if let Ok(v) = value.parse::<i32>() { params.set_scalar(i, v); }
+                continue;
+            }
+            if let Some(i) = ARRAY_NAMES.iter().position(|&n| n == name) {
+                let parsed: Vec<i32> = value.split_whitespace().filter_map(|t| t.parse().ok()).collect();
+                let expected = if i == 0 { 8 } else { 64 };
+                if parsed.len() == expected { params.array_mut(i).copy_from_slice(&parsed); }
+            }
+        }
+        params
+    }
+
+    pub fn load_path(path: &str) -> std::io::Result<Self> {
+        let s = std::fs::read_to_string(path)?;
+        Ok(Self::load_str(&s))
+    }
+
+    /// Loads from the path named by `EVAL_PARAMS_PATH`, if set and readable; falls back to
+    /// `default()` otherwise (e.g. the env var is unset, or the file can't be read).
+    pub fn load_from_env() -> Self {
+        std::env::var("EVAL_PARAMS_PATH")
+            .ok()
+            .and_then(|path| Self::load_path(&path).ok())
+            .unwrap_or_default()
+    }
+}
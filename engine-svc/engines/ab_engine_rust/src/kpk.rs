@@ -0,0 +1,266 @@
+// ab_engine_rust/src/kpk.rs
+//
+// King-and-pawn-vs-king bitbase. With one side up a lone pawn and otherwise bare kings,
+// the side to move either wins (the pawn queens) or draws (the defender holds) -- the
+// defender can never do better than a draw, so one bit per position is enough. The table
+// is built once at startup by retrograde (backward) fixed-point analysis over every
+// reachable position, canonicalized with the pawn side always cast as "White" and its
+// pawn mirrored onto the a..d files (KPK has no other left/right asymmetry). Positions
+// are built and walked as real `chess::Board`s via FEN round-tripping, trading a slower
+// one-time startup for reusing the crate's own move generation/check rules instead of
+// re-deriving king/pawn legality by hand -- the same trade-off `tb.rs` makes for probes.
+
+use std::str::FromStr;
+
+use chess::{Board, BoardStatus, Color, MoveGen, Piece, Square};
+
+use crate::types::MATE;
+
+// Kept well clear of `MATE` (and of `tb.rs`'s `TB_WIN`) so neither a genuine forced mate
+// nor a Syzygy verdict the search already trusts more gets shadowed by this one.
+const KPK_WIN: i32 = MATE - 2000;
+
+// Pawn file is canonicalized onto a..d; rank is restricted to 2..7 (a pawn can never sit
+// on the 1st/8th rank).
+const FILES: usize = 4;
+const RANKS: usize = 6;
+const KINGS: usize = 64;
+const SIDES: usize = 2;
+const TABLE_LEN: usize = KINGS * KINGS * FILES * RANKS * SIDES;
+
+#[inline]
+fn table_index(wk: usize, bk: usize, file: usize, rank: usize, white_to_move: bool) -> usize {
+    let side = if white_to_move { 0 } else { 1 };
+    (((wk * KINGS + bk) * FILES + file) * RANKS + rank) * SIDES + side
+}
+
+#[inline]
+fn flip_file(i: usize) -> usize { (i & !7) | (7 - (i & 7)) }
+
+/// One canonical KPK position: "White" is always the side with the extra pawn.
+#[derive(Clone, Copy)]
+struct Pos {
+    wk: Square,
+    bk: Square,
+    pawn: Square,
+    white_to_move: bool,
+}
+
+impl Pos {
+    fn decode(wk: usize, bk: usize, file: usize, rank: usize, white_to_move: bool) -> Option<Self> {
+        if wk == bk { return None; }
+        let pawn_idx = (rank + 1) * 8 + file; // rank offset 0 => rank 2 (board index 1)
+        if pawn_idx == wk || pawn_idx == bk { return None; }
+        Some(Self {
+            wk: unsafe { Square::new(wk as u8) },
+            bk: unsafe { Square::new(bk as u8) },
+            pawn: unsafe { Square::new(pawn_idx as u8) },
+            white_to_move,
+        })
+    }
+
+    /// Reads a canonical position back out of an arbitrary board: exactly a white king,
+    /// black king and one white pawn, nothing else. `None` if the pawn was captured or
+    /// promoted (both leave the table's domain) or the board just isn't KPK shaped.
+    fn from_board(b: &Board) -> Option<Self> {
+        let extra = b.pieces(Piece::Knight) | b.pieces(Piece::Bishop)
+            | b.pieces(Piece::Rook) | b.pieces(Piece::Queen);
+        if extra.popcnt() != 0 { return None; }
+        if (b.pieces(Piece::Pawn) & b.color_combined(Color::Black)).popcnt() != 0 { return None; }
+        let wp = b.color_combined(Color::White) & b.pieces(Piece::Pawn);
+        if wp.popcnt() != 1 { return None; }
+
+        let wk = (b.color_combined(Color::White) & b.pieces(Piece::King)).to_square();
+        let bk = (b.color_combined(Color::Black) & b.pieces(Piece::King)).to_square();
+        Some(Self { wk, bk, pawn: wp.to_square(), white_to_move: b.side_to_move() == Color::White })
+    }
+
+    fn index(&self) -> usize {
+        let file = self.pawn.to_index() % 8;
+        let rank = self.pawn.to_index() / 8 - 1;
+        table_index(self.wk.to_index(), self.bk.to_index(), file, rank, self.white_to_move)
+    }
+
+    /// Builds the actual board so legality, move generation and checkmate/stalemate
+    /// detection all go through the `chess` crate's rules rather than being re-derived.
+    fn to_board(self) -> Option<Board> {
+        let mut squares = [b'.'; 64];
+        squares[self.wk.to_index()] = b'K';
+        squares[self.bk.to_index()] = b'k';
+        squares[self.pawn.to_index()] = b'P';
+
+        let mut board_field = String::new();
+        for r in (0..8).rev() {
+            let mut empties = 0u32;
+            for f in 0..8 {
+                let c = squares[r * 8 + f];
+                if c == b'.' {
+                    empties += 1;
+                } else {
+                    if empties > 0 { board_field.push_str(&empties.to_string()); empties = 0; }
+                    board_field.push(c as char);
+                }
+            }
+            if empties > 0 { board_field.push_str(&empties.to_string()); }
+            if r > 0 { board_field.push('/'); }
+        }
+
+        let side = if self.white_to_move { "w" } else { "b" };
+        Board::from_str(&format!("{} {} - - 0 1", board_field, side)).ok()
+    }
+}
+
+pub struct Kpk {
+    // 1 bit per index: set if the position is a win for the pawn side.
+    win: Vec<u64>,
+}
+
+impl Kpk {
+    #[inline]
+    fn get(&self, idx: usize) -> bool { (self.win[idx / 64] >> (idx % 64)) & 1 != 0 }
+    #[inline]
+    fn set(&mut self, idx: usize) { self.win[idx / 64] |= 1u64 << (idx % 64); }
+
+    /// Builds the table: a first pass classifies every checkmate/stalemate, then
+    /// retrograde sweeps repeatedly classify whatever's left from its successors until a
+    /// full pass changes nothing.
+    pub fn generate() -> Self {
+        let mut kpk = Self { win: vec![0u64; TABLE_LEN.div_ceil(64)] };
+        let mut known = vec![false; TABLE_LEN];
+
+        for_each_pos(|pos, idx| {
+            let Some(board) = pos.to_board() else { return };
+            match board.status() {
+                BoardStatus::Checkmate => {
+                    // Only the defender (bare king) can ever be mated with this material.
+                    if !pos.white_to_move { kpk.set(idx); }
+                    known[idx] = true;
+                }
+                BoardStatus::Stalemate => known[idx] = true, // draw: bit stays 0
+                BoardStatus::Ongoing => {}
+            }
+        });
+
+        loop {
+            let mut changed = false;
+            for_each_pos(|pos, idx| {
+                if known[idx] { return; }
+                let Some(board) = pos.to_board() else { return };
+
+                let mut all_known = true;
+                let mut resolved: Option<bool> = None;
+                for mv in MoveGen::new_legal(&board) {
+                    let nb = board.make_move_new(mv);
+                    let succ_is_win = match Pos::from_board(&nb) {
+                        Some(succ) => {
+                            let succ_idx = succ.index();
+                            if !known[succ_idx] { all_known = false; continue; }
+                            kpk.get(succ_idx)
+                        }
+                        // Outside the table: the pawn either promoted or was captured by
+                        // the black king (bare kings left -- a draw). A queen promotion
+                        // wins unless the black king can take the queen at once or is
+                        // stalemated; an underpromotion counts as a draw.
+                        None => {
+                            let wq = nb.pieces(Piece::Queen) & nb.color_combined(Color::White);
+                            wq.popcnt() > 0
+                                && nb.status() != BoardStatus::Stalemate
+                                && !MoveGen::new_legal(&nb).any(|m| m.get_dest() == wq.to_square())
+                        }
+                    };
+                    let wants_win = pos.white_to_move;
+                    if succ_is_win == wants_win { resolved = Some(wants_win); break; }
+                }
+
+                if let Some(is_win) = resolved {
+                    if is_win { kpk.set(idx); }
+                    known[idx] = true;
+                    changed = true;
+                } else if all_known {
+                    // No successor gave the mover what it wanted: White with no winning
+                    // reply settles for a draw; Black with no drawing reply is lost.
+                    if !pos.white_to_move { kpk.set(idx); }
+                    known[idx] = true;
+                    changed = true;
+                }
+            });
+            if !changed { break; }
+        }
+
+        kpk
+    }
+
+    /// Whether `b`'s material is exactly a lone king against a king and one pawn, and if
+    /// so which color carries the pawn.
+    fn material_sides(b: &Board) -> Option<(Color, Color)> {
+        let extra = b.pieces(Piece::Knight) | b.pieces(Piece::Bishop)
+            | b.pieces(Piece::Rook) | b.pieces(Piece::Queen);
+        if extra.popcnt() != 0 { return None; }
+        for &(strong, weak) in &[(Color::White, Color::Black), (Color::Black, Color::White)] {
+            let strong_pawns = (b.color_combined(strong) & b.pieces(Piece::Pawn)).popcnt();
+            let weak_pawns = (b.color_combined(weak) & b.pieces(Piece::Pawn)).popcnt();
+            if strong_pawns == 1 && weak_pawns == 0 { return Some((strong, weak)); }
+        }
+        None
+    }
+
+    /// Probes the table for `b`, returning a decisive `±(KPK_WIN - ply)` or a `0` draw
+    /// from the side-to-move's perspective, or `None` if the material isn't KPK.
+    pub fn probe(&self, b: &Board, ply: i32) -> Option<i32> {
+        let (strong, weak) = Self::material_sides(b)?;
+
+        // Mirror the whole board vertically when Black carries the pawn, so it plays the
+        // "White" role (pawn advancing toward higher ranks) the table was built for.
+        let mirror = strong == Color::Black;
+        let orient = |sq: Square| -> usize {
+            let i = sq.to_index();
+            if mirror { i ^ 56 } else { i }
+        };
+
+        let mut wk = orient((b.color_combined(strong) & b.pieces(Piece::King)).to_square());
+        let mut bk = orient((b.color_combined(weak) & b.pieces(Piece::King)).to_square());
+        let mut pawn = orient((b.color_combined(strong) & b.pieces(Piece::Pawn)).to_square());
+
+        let mut file = pawn % 8;
+        if file >= 4 {
+            wk = flip_file(wk);
+            bk = flip_file(bk);
+            pawn = flip_file(pawn);
+            file = 7 - file;
+        }
+        let rank = pawn / 8;
+        if rank == 0 || rank == 7 { return None; } // pawn can't actually sit here
+
+        // "White" in the table is always the strong (pawn) side, so this is also "is the
+        // strong side on move here".
+        let white_to_move = b.side_to_move() == strong;
+        let idx = table_index(wk, bk, file, rank - 1, white_to_move);
+        let win_for_strong = self.get(idx);
+
+        // The defender can never do better than a draw, so a win is only ever "for
+        // strong" -- translate that into a side-to-move-relative score.
+        Some(match (win_for_strong, white_to_move) {
+            (true, true) => KPK_WIN - ply,   // strong to move, and it's winning
+            (true, false) => -(KPK_WIN - ply), // weak to move, but strong is winning
+            (false, _) => 0,                 // drawn regardless of who's on move
+        })
+    }
+}
+
+/// Iterates every `(Pos, table index)` pair, skipping the overlapping-square combinations
+/// `Pos::decode` rejects.
+fn for_each_pos(mut f: impl FnMut(Pos, usize)) {
+    for wk in 0..KINGS {
+        for bk in 0..KINGS {
+            for file in 0..FILES {
+                for rank in 0..RANKS {
+                    for &stm in &[true, false] {
+                        if let Some(pos) = Pos::decode(wk, bk, file, rank, stm) {
+                            f(pos, pos.index());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
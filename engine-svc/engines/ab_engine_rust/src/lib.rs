@@ -2,13 +2,23 @@
 
 pub mod types;
 pub mod eval;
+pub mod evalparams;
 pub mod ordering;
 pub mod tt;
 pub mod search;
+pub mod options;
+pub mod timeman;
+pub mod tb;
+pub mod kpk;
 
 // (Optional) nice re-exports so main.rs can `use engine::search::Search;` etc.
 pub use types::*;
 pub use eval::ClassicalEval;
+pub use evalparams::EvalParams;
 pub use ordering::{Ordering as MoveOrdering, Killers, History};
 pub use tt::TT;
 pub use search::Search;
+pub use options::EngineOptions;
+pub use timeman::TimeBudget;
+pub use tb::Tb;
+pub use kpk::Kpk;
@@ -1,13 +1,151 @@
-use chess::{Board, ChessMove, MoveGen, Square};
+use chess::{Board, ChessMove, Color, MoveGen, Square};
+use engine::evalparams::EvalParams;
+use engine::options::EngineOptions;
 use engine::search::{Search, root_search, pv_line_from_tt, current_best_or_default};
+use engine::tb::Tb;
+use engine::kpk::Kpk;
+use engine::timeman;
+use engine::tt::TT;
 use engine::types::*;
 use std::io::{self, BufRead, Write};
 use std::str::FromStr;
-use std::sync::{Arc};
+use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
 use std::time::{Duration, Instant};
 
+// Used by `run_smp_worker` to decide whether a soft time limit can be borrowed against:
+// the best move must have held for this many consecutive iterations, with the score
+// swinging by no more than this many centipawns, before the line counts as "stable".
+const STABILITY_ITERS: i32 = 3;
+const SCORE_SWING_CP: i32 = 50;
+
+/// The best move/score found so far, shared across Lazy SMP workers. Workers only ever
+/// publish a result from a *deeper* completed iteration than what's already here, so the
+/// reported bestmove always reflects the deepest iteration any worker finished.
+#[derive(Default)]
+struct SharedBest {
+    depth: i32,
+    best_move: Option<ChessMove>,
+}
+
+/// Runs iterative deepening on `b0` against the shared table `tt`, publishing each
+/// completed iteration's result into `shared`. `worker_id` both seeds a small amount of
+/// move-ordering/depth jitter (so Lazy SMP workers diverge instead of redoing the same
+/// search) and gates UCI `info` reporting to the main worker (id 0) only.
+#[allow(clippy::too_many_arguments)]
+fn run_smp_worker(
+    worker_id: usize,
+    b0: Board,
+    game_history: Arc<Vec<u64>>,
+    tt: Arc<TT>,
+    stop: Arc<AtomicBool>,
+    shared: Arc<Mutex<SharedBest>>,
+    start: Instant,
+    soft_limit: Option<Duration>,
+    hard_deadline: Option<Duration>,
+    max_depth: i32,
+    contempt: i32,
+    eval_params: EvalParams,
+    tb: Option<Arc<Tb>>,
+    kpk: Arc<Kpk>,
+) {
+    let mut search = Search::with_tt(Arc::clone(&stop), tt, contempt, eval_params, tb, kpk);
+    search.set_hard_deadline(hard_deadline.map(|hd| start + hd));
+    // Helper threads get a small worker_id-derived jitter so they don't walk an identical
+    // move order off the shared TT; the main worker (id 0) keeps exact, unperturbed ordering.
+    search.set_move_jitter(worker_id as u64);
+
+    let mut last_score = search.evaluate(&b0);
+    let report = worker_id == 0;
+
+    // Odd-numbered helper threads skip every even depth so they don't retread the exact
+    // same iterative-deepening schedule as the main worker; this is the usual Lazy SMP
+    // "depth skipping" trick for diversifying otherwise-identical search trees that
+    // share one TT.
+    let skip_depth = |d: i32| worker_id > 0 && worker_id % 2 == 1 && d % 2 == 0;
+    // Even-numbered helper threads instead start one ply deeper, so they don't all
+    // re-walk the same depth-1/depth-2 iterations as the main worker before diverging.
+    let start_depth = if worker_id > 0 && worker_id.is_multiple_of(2) { 2 } else { 1 };
+
+    // The soft limit is a target, not a hard stop: if the best move just changed or the
+    // score is still swinging, it's worth borrowing a bit more time (up to the hard
+    // deadline) rather than reporting a move iterative deepening isn't confident in yet.
+    let mut prev_best_move: Option<ChessMove> = None;
+    let mut stable_iters = 0i32;
+
+    // True while iterative deepening should keep going: always below the soft limit,
+    // and past it only if the line is still unstable and there's hard-deadline room left.
+    // Borrowing all the way to `hard_deadline` on an unstable line is exactly what makes
+    // `check_time`'s internal stop fire instead of this loop ending on its own -- relies
+    // on the `go` handler still emitting `bestmove` in that case (see its `sent` guard).
+    let keep_going = |elapsed: Duration, stable_iters: i32| -> bool {
+        let Some(tl) = soft_limit else { return true; };
+        if elapsed < tl { return true; }
+        if stable_iters >= STABILITY_ITERS { return false; }
+        hard_deadline.is_some_and(|hd| elapsed < hd)
+    };
+
+    for d in start_depth..=max_depth {
+        if skip_depth(d) { continue; }
+        if !keep_going(start.elapsed(), stable_iters) { break; }
+        if stop.load(Ordering::Relaxed) { break; }
+
+        search.on_new_iter();
+
+        let mut window = ASP_WINDOW;
+        let mut alpha = last_score - window;
+        let mut beta = last_score + window;
+
+        let mut score;
+        let iter_best_move: Option<ChessMove>;
+        loop {
+            let (best_move, sc) = root_search(&mut search, &b0, d, alpha, beta, &game_history);
+            score = sc;
+            if (score <= alpha || score >= beta) && window < ASP_MAX_WIDEN {
+                window = (window * 2).min(ASP_MAX_WIDEN);
+                alpha = score - window;
+                beta = score + window;
+                continue;
+            } else {
+                iter_best_move = best_move;
+                // An iteration cut short by `stop` is not complete, so it isn't published.
+                if let Some(m) = best_move.filter(|_| !stop.load(Ordering::Relaxed)) {
+                    let mut guard = shared.lock().unwrap();
+                    if d > guard.depth {
+                        guard.depth = d;
+                        guard.best_move = Some(m);
+                    }
+                }
+                break;
+            }
+        }
+
+        if iter_best_move.is_some() && iter_best_move == prev_best_move
+            && (score - last_score).abs() <= SCORE_SWING_CP
+        {
+            stable_iters += 1;
+        } else {
+            stable_iters = 0;
+        }
+        prev_best_move = iter_best_move;
+
+        last_score = clamp(score, -INF + 1, INF - 1);
+
+        if report {
+            let elapsed = start.elapsed().as_secs_f64().max(1e-6);
+            let nps = (search.nodes as f64 / elapsed) as u64;
+            let pv = pv_line_from_tt(b0, &search.tt, d as usize);
+            let pv_str = pv.iter().map(|m| m.to_string()).collect::<Vec<_>>().join(" ");
+            println!("info depth {} nodes {} nps {} score cp {} pv {}", d, search.nodes, nps, last_score, pv_str);
+            io::stdout().flush().ok();
+        }
+
+        if stop.load(Ordering::Relaxed) { break; }
+        if !keep_going(start.elapsed(), stable_iters) { break; }
+    }
+}
+
 fn parse_uci_move(s: &str) -> Option<ChessMove> {
     if s.len() < 4 { return None; }
     let from = Square::from_str(&s[0..2]).ok()?;
@@ -24,12 +162,26 @@ fn parse_uci_move(s: &str) -> Option<ChessMove> {
     Some(ChessMove::new(from, to, promo))
 }
 
+/// Splits a `setoption name <name> value <value>` command into its name/value parts.
+/// The name and value may each contain spaces, so this locates the `name `/` value `
+/// markers rather than splitting on whitespace.
+fn parse_setoption(cmd: &str) -> Option<(String, String)> {
+    let rest = cmd.strip_prefix("setoption ")?;
+    let after_name = &rest[rest.find("name ")? + 5..];
+    match after_name.find(" value ") {
+        Some(vi) => Some((after_name[..vi].trim().to_string(), after_name[vi + 7..].trim().to_string())),
+        None => Some((after_name.trim().to_string(), String::new())),
+    }
+}
+
 fn main() -> io::Result<()> {
     let stdin = io::stdin();
     let mut stdout = io::stdout();
 
     println!("id name PyRefEngine (AB-only)");
     println!("id author open-source");
+    EngineOptions::default().print_uci_options();
+    println!("option name EvalParamsFile type string default <empty>");
     println!("uciok");
     stdout.flush()?;
 
@@ -44,9 +196,26 @@ fn main() -> io::Result<()> {
     });
 
     let mut board = Board::default();
+    // Hashes of the positions played since the last irreversible (capture/pawn) move,
+    // oldest first, ending with `board` itself. Seeds each search's repetition check so
+    // threefold draws are caught across the played game, not just within the search tree.
+    let mut game_history: Vec<u64> = vec![board_key(&board)];
     let mut search_handle: Option<std::thread::JoinHandle<()>> = None;
+    // The in-progress search's shared best move, so an explicit `stop` can report the
+    // deepest completed iteration instead of falling back to a 1-ply heuristic. `None`
+    // whenever no search is running (cleared alongside `search_handle`).
+    let mut current_shared_best: Option<Arc<Mutex<SharedBest>>> = None;
     let stop_flag = Arc::new(AtomicBool::new(false));
     let bestmove_sent = Arc::new(AtomicBool::new(false));
+    let mut options = EngineOptions::with_env_defaults();
+    // `EVAL_PARAMS_PATH` lets a tuned weight set ship as data instead of a recompile;
+    // `setoption name EvalParamsFile` below does the same thing mid-session.
+    let mut eval_params = EvalParams::load_from_env();
+    // `SYZYGY_PATH`-loaded endgame tables, shared read-only across every Lazy SMP worker.
+    let syzygy: Option<Arc<Tb>> = Tb::load_from_env().map(Arc::new);
+    // King-and-pawn-vs-king bitbase: generated once up front (no external file needed)
+    // and shared read-only the same way `syzygy` is.
+    let kpk: Arc<Kpk> = Arc::new(Kpk::generate());
 
     while let Ok(cmd) = rx_cmd.recv() {
         println!("info string dbg=recv '{}'", cmd);
@@ -55,10 +224,28 @@ fn main() -> io::Result<()> {
         if cmd == "uci" {
             println!("id name PyRefEngine (AB-only)");
             println!("id author open-source");
+            options.print_uci_options();
+            println!("option name EvalParamsFile type string default <empty>");
             println!("uciok");
             stdout.flush()?;
             continue;
         }
+        if cmd.starts_with("setoption ") {
+            if let Some((name, value)) = parse_setoption(&cmd) {
+                if name == "EvalParamsFile" {
+                    match EvalParams::load_path(&value) {
+                        Ok(p) => eval_params = p,
+                        Err(e) => println!("info string dbg=eval-params-load-error {}", e),
+                    }
+                } else if !options.apply(&name, &value) {
+                    println!("info string dbg=unknown-option {}", name);
+                }
+            } else {
+                println!("info string dbg=setoption-parse-error {}", cmd);
+            }
+            stdout.flush()?;
+            continue;
+        }
         if cmd == "isready" {
             println!("readyok");
             stdout.flush()?;
@@ -70,25 +257,27 @@ fn main() -> io::Result<()> {
                 let _ = h.join();
                 stop_flag.store(false, Ordering::Relaxed);
                 bestmove_sent.store(false, Ordering::Relaxed);
+                current_shared_best = None;
             }
             board = Board::default();
+            game_history = vec![board_key(&board)];
             stdout.flush()?;
             continue;
         }
         if cmd.starts_with("position ") {
             if let Some(after) = cmd.strip_prefix("position ") {
                 let parts: Vec<&str> = after.split_whitespace().collect();
-                let mut idx = 0;
-                if parts.get(0) == Some(&"startpos") {
+                let idx;
+                if parts.first() == Some(&"startpos") {
                     board = Board::default();
                     idx = 1;
-                } else if parts.get(0) == Some(&"fen") {
+                } else if parts.first() == Some(&"fen") {
                     if parts.len() >= 7 {
                         let fen = parts[1..7].join(" ");
                         match Board::from_str(&fen) {
                             Ok(b) => board = b,
                             Err(e) => {
-                                println!("info string dbg=position-parse-error {}:{}", "FEN", e);
+                                println!("info string dbg=position-parse-error FEN:{}", e);
                                 board = Board::default();
                                 stdout.flush()?;
                                 continue;
@@ -96,23 +285,30 @@ fn main() -> io::Result<()> {
                         }
                         idx = 7;
                     } else {
-                        println!("info string dbg=position-parse-error {}:{}", "FEN", "expected 6 tokens");
+                        println!("info string dbg=position-parse-error FEN:expected 6 tokens");
                         board = Board::default();
                         stdout.flush()?;
                         continue;
                     }
                 } else {
-                    println!("info string dbg=position-parse-error {}:{}", "SYNTAX", "expected startpos or fen");
+                    println!("info string dbg=position-parse-error SYNTAX:expected startpos or fen");
                     board = Board::default();
                     stdout.flush()?;
                     continue;
                 }
 
+                let mut history = vec![board_key(&board)];
+                let mut reset_idx = 0usize;
+
                 if idx < parts.len() && parts[idx] == "moves" {
                     for mv_str in &parts[idx + 1..] {
                         if let Some(mv) = parse_uci_move(mv_str) {
                             if MoveGen::new_legal(&board).any(|m| m == mv) {
                                 board = board.make_move_new(mv);
+                                history.push(board_key(&board));
+                                if halfmove_clock_from_fen(&board) == 0 {
+                                    reset_idx = history.len() - 1;
+                                }
                             } else {
                                 println!("info string dbg=bad-move {}", mv_str);
                             }
@@ -121,6 +317,8 @@ fn main() -> io::Result<()> {
                         }
                     }
                 }
+
+                game_history = history[reset_idx..].to_vec();
             }
             stdout.flush()?;
             continue;
@@ -130,6 +328,11 @@ fn main() -> io::Result<()> {
             let mut depth: i32 = DEFAULT_DEPTH;
             let mut rollouts: i32 = DEFAULT_ROLLOUTS;
             let mut movetime_ms: Option<u64> = None;
+            let mut wtime_ms: Option<u64> = None;
+            let mut btime_ms: Option<u64> = None;
+            let mut winc_ms: Option<u64> = None;
+            let mut binc_ms: Option<u64> = None;
+            let mut movestogo: Option<u32> = None;
 
             let parts: Vec<&str> = cmd.split_whitespace().collect();
             let mut i = 1;
@@ -138,6 +341,11 @@ fn main() -> io::Result<()> {
                     "depth" => { if let Ok(d) = parts[i + 1].parse::<i32>() { depth = d; } i += 2; }
                     "rollouts" => { if let Ok(r) = parts[i + 1].parse::<i32>() { rollouts = r; } i += 2; }
                     "movetime" => { if let Ok(ms) = parts[i + 1].parse::<u64>() { movetime_ms = Some(ms); } i += 2; }
+                    "wtime" => { if let Ok(ms) = parts[i + 1].parse::<u64>() { wtime_ms = Some(ms); } i += 2; }
+                    "btime" => { if let Ok(ms) = parts[i + 1].parse::<u64>() { btime_ms = Some(ms); } i += 2; }
+                    "winc" => { if let Ok(ms) = parts[i + 1].parse::<u64>() { winc_ms = Some(ms); } i += 2; }
+                    "binc" => { if let Ok(ms) = parts[i + 1].parse::<u64>() { binc_ms = Some(ms); } i += 2; }
+                    "movestogo" => { if let Ok(m) = parts[i + 1].parse::<u32>() { movestogo = Some(m); } i += 2; }
                     _ => i += 1,
                 }
             }
@@ -150,64 +358,90 @@ fn main() -> io::Result<()> {
                 let _ = h.join();
                 stop_flag.store(false, Ordering::Relaxed);
                 bestmove_sent.store(false, Ordering::Relaxed);
+                current_shared_best = None;
             }
+            // No search is running now; a root tablebase move from the previous `go` may
+            // still have the flag set without a search handle to clear it above.
+            bestmove_sent.store(false, Ordering::Relaxed);
 
             let b0 = board;
+            let history0 = game_history.clone();
             let stop = Arc::clone(&stop_flag);
             let sent = Arc::clone(&bestmove_sent);
+            let hash_mb = options.hash_mb;
+            let move_overhead_ms = options.move_overhead_ms;
+            let contempt = options.contempt;
+            let threads = options.threads.max(1);
+            let eval_params = eval_params.clone();
 
-            search_handle = Some(thread::spawn(move || {
-                let start = Instant::now();
-                let time_limit = movetime_ms.map(Duration::from_millis);
-
-                let mut search = Search::new(Arc::clone(&stop));
-
-                let mut last_score = search.evaluate(&b0);
-                let mut root_best: Option<ChessMove> = None;
-
-                let max_depth = depth.max(1).min(MAX_AB_DEPTH);
-                for d in 1..=max_depth {
-                    if let Some(tl) = time_limit { if start.elapsed() >= tl { break; } }
-                    if stop.load(Ordering::Relaxed) { break; }
-
-                    search.on_new_iter();
-
-                    let mut window = ASP_WINDOW;
-                    let mut alpha = last_score - window;
-                    let mut beta  = last_score + window;
+            // Either an explicit `movetime`, a clock-based budget derived from
+            // wtime/btime/winc/binc/movestogo, or no limit at all (fixed-depth search).
+            let (soft_limit, hard_deadline): (Option<Duration>, Option<Duration>) = if let Some(mt) = movetime_ms {
+                (Some(Duration::from_millis(mt)), Some(Duration::from_millis(mt)))
+            } else if wtime_ms.is_some() || btime_ms.is_some() {
+                let (remaining_ms, inc_ms) = if b0.side_to_move() == Color::White {
+                    (wtime_ms.unwrap_or(0), winc_ms.unwrap_or(0))
+                } else {
+                    (btime_ms.unwrap_or(0), binc_ms.unwrap_or(0))
+                };
+                let budget = timeman::allocate(remaining_ms, inc_ms, movestogo, move_overhead_ms);
+                (Some(budget.soft), Some(budget.hard))
+            } else {
+                (None, None)
+            };
 
-                    let mut score;
-                    loop {
-                        let (best_move, sc) = root_search(&mut search, &b0, d, alpha, beta);
-                        score = sc;
-                        if (score <= alpha || score >= beta) && window < ASP_MAX_WIDEN {
-                            window = (window * 2).min(ASP_MAX_WIDEN);
-                            alpha = score - window;
-                            beta  = score + window;
-                            continue;
-                        } else {
-                            if let Some(m) = best_move { root_best = Some(m); }
-                            break;
-                        }
+            // Root Syzygy hit: play the DTZ-best move directly rather than searching --
+            // the tables already know the perfect result, and DTZ (not just WDL) is what
+            // keeps a won conversion from drifting into a fifty-move draw.
+            if let Some(tb) = &syzygy {
+                if let Some(m) = tb.probe_root_move(&b0) {
+                    if !bestmove_sent.swap(true, Ordering::Relaxed) {
+                        println!("bestmove {}", m);
+                        stdout.flush()?;
                     }
+                    continue;
+                }
+            }
 
-                    last_score = clamp(score, -INF + 1, INF - 1);
-
-                    let elapsed = start.elapsed().as_secs_f64().max(1e-6);
-                    let nps = (search.nodes as f64 / elapsed) as u64;
-                    let pv = pv_line_from_tt(b0, &search.tt, d as usize);
-                    let pv_str = pv.iter().map(|m| m.to_string()).collect::<Vec<_>>().join(" ");
-                    println!("info depth {} nodes {} nps {} score cp {} pv {}", d, search.nodes, nps, last_score, pv_str);
-                    println!("info string dbg=iter depth={}", d);
-                    io::stdout().flush().ok();
+            // One shared lockless TT per `go` call, handed to every Lazy SMP worker.
+            let shared_tt = Arc::new(TT::new_from_mb(hash_mb));
+            let shared_best = Arc::new(Mutex::new(SharedBest::default()));
+            current_shared_best = Some(Arc::clone(&shared_best));
+            let history0 = Arc::new(history0);
+            let max_depth = depth.clamp(1, MAX_AB_DEPTH);
+            let syzygy = syzygy.clone();
+            let kpk = Arc::clone(&kpk);
 
-                    if stop.load(Ordering::Relaxed) { break; }
-                    if let Some(tl) = time_limit { if start.elapsed() >= tl { break; } }
-                }
+            search_handle = Some(thread::spawn(move || {
+                let start = Instant::now();
 
-                if stop.load(Ordering::Relaxed) { return; }
+                let workers: Vec<_> = (0..threads)
+                    .map(|worker_id| {
+                        let history0 = Arc::clone(&history0);
+                        let tt = Arc::clone(&shared_tt);
+                        let stop = Arc::clone(&stop);
+                        let shared_best = Arc::clone(&shared_best);
+                        let eval_params = eval_params.clone();
+                        let tb = syzygy.clone();
+                        let kpk = Arc::clone(&kpk);
+                        thread::spawn(move || {
+                            run_smp_worker(
+                                worker_id, b0, history0, tt, stop, shared_best,
+                                start, soft_limit, hard_deadline, max_depth, contempt, eval_params, tb, kpk,
+                            );
+                        })
+                    })
+                    .collect();
+                for w in workers { let _ = w.join(); }
 
+                // Don't gate this on `stop`: `check_time` sets the very same shared flag
+                // once the hard deadline passes, which is the normal way a time-limited
+                // search ends (e.g. every `go movetime` search, since soft == hard there).
+                // `sent` alone is the right guard -- the explicit `stop` command handler
+                // already claims it before this thread gets here, so there's no double
+                // `bestmove` either way.
                 if !sent.swap(true, Ordering::Relaxed) {
+                    let root_best = shared_best.lock().unwrap().best_move;
                     let best_uci = if let Some(m) = root_best { m.to_string() } else { current_best_or_default(&b0) };
                     println!("bestmove {}", best_uci);
                     io::stdout().flush().ok();
@@ -220,7 +454,11 @@ fn main() -> io::Result<()> {
         if cmd == "stop" {
             stop_flag.store(true, Ordering::Relaxed);
             if !bestmove_sent.swap(true, Ordering::Relaxed) {
-                println!("bestmove {}", current_best_or_default(&board));
+                // Report the deepest completed iteration's move if the search got that
+                // far; only fall back to the 1-ply heuristic when none has finished yet.
+                let root_best = current_shared_best.as_ref().and_then(|sb| sb.lock().unwrap().best_move);
+                let best_uci = root_best.map(|m| m.to_string()).unwrap_or_else(|| current_best_or_default(&board));
+                println!("bestmove {}", best_uci);
                 stdout.flush()?;
             }
             continue;
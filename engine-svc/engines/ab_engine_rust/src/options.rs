@@ -0,0 +1,84 @@
+// ab_engine_rust/src/options.rs
+//
+// UCI-tunable engine options, set via `setoption name <X> value <Y>`.
+
+#[derive(Clone, Copy, Debug)]
+pub struct EngineOptions {
+    pub hash_mb: usize,
+    pub threads: usize,
+    pub move_overhead_ms: u64,
+    pub contempt: i32,
+}
+
+pub const DEFAULT_HASH_MB: usize = 64;
+pub const MIN_HASH_MB: usize = 1;
+pub const MAX_HASH_MB: usize = 4096;
+
+pub const DEFAULT_THREADS: usize = 1;
+pub const MIN_THREADS: usize = 1;
+pub const MAX_THREADS: usize = 64;
+
+pub const DEFAULT_MOVE_OVERHEAD_MS: u64 = 10;
+pub const MIN_MOVE_OVERHEAD_MS: u64 = 0;
+pub const MAX_MOVE_OVERHEAD_MS: u64 = 5000;
+
+pub const DEFAULT_CONTEMPT: i32 = 0;
+pub const MIN_CONTEMPT: i32 = -100;
+pub const MAX_CONTEMPT: i32 = 100;
+
+impl Default for EngineOptions {
+    fn default() -> Self {
+        Self {
+            hash_mb: DEFAULT_HASH_MB,
+            threads: DEFAULT_THREADS,
+            move_overhead_ms: DEFAULT_MOVE_OVERHEAD_MS,
+            contempt: DEFAULT_CONTEMPT,
+        }
+    }
+}
+
+impl EngineOptions {
+    /// Reads a `THREADS` environment variable as the startup default, so a Lazy SMP worker
+    /// count can be set before a GUI ever sends `setoption name Threads` (which still
+    /// overrides this at any point mid-session).
+    pub fn with_env_defaults() -> Self {
+        let mut opts = Self::default();
+        if let Ok(v) = std::env::var("THREADS") {
+            if let Ok(n) = v.parse::<usize>() { opts.threads = n.clamp(MIN_THREADS, MAX_THREADS); }
+        }
+        opts
+    }
+
+    /// Prints the `option name ...` lines a GUI expects in response to `uci`.
+    pub fn print_uci_options(&self) {
+        println!("option name Hash type spin default {} min {} max {}", DEFAULT_HASH_MB, MIN_HASH_MB, MAX_HASH_MB);
+        println!("option name Threads type spin default {} min {} max {}", DEFAULT_THREADS, MIN_THREADS, MAX_THREADS);
+        println!("option name Move Overhead type spin default {} min {} max {}", DEFAULT_MOVE_OVERHEAD_MS, MIN_MOVE_OVERHEAD_MS, MAX_MOVE_OVERHEAD_MS);
+        println!("option name Contempt type spin default {} min {} max {}", DEFAULT_CONTEMPT, MIN_CONTEMPT, MAX_CONTEMPT);
+    }
+
+    /// Applies a parsed `setoption name <name> value <value>` command, clamping to the
+    /// advertised range. Returns `false` if `name` isn't one of ours (GUIs send setoption
+    /// for options other engines expose too, so unknown names are not an error).
+    pub fn apply(&mut self, name: &str, value: &str) -> bool {
+        match name {
+            "Hash" => {
+                if let Ok(v) = value.parse::<usize>() { self.hash_mb = v.clamp(MIN_HASH_MB, MAX_HASH_MB); }
+                true
+            }
+            "Threads" => {
+                if let Ok(v) = value.parse::<usize>() { self.threads = v.clamp(MIN_THREADS, MAX_THREADS); }
+                true
+            }
+            "Move Overhead" => {
+                if let Ok(v) = value.parse::<u64>() { self.move_overhead_ms = v.clamp(MIN_MOVE_OVERHEAD_MS, MAX_MOVE_OVERHEAD_MS); }
+                true
+            }
+            "Contempt" => {
+                if let Ok(v) = value.parse::<i32>() { self.contempt = v.clamp(MIN_CONTEMPT, MAX_CONTEMPT); }
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use chess::{Board, ChessMove, MoveGen, Color};
+use chess::{
+    get_bishop_moves, get_king_moves, get_knight_moves, get_pawn_attacks, get_rook_moves,
+    BitBoard, Board, ChessMove, MoveGen, Color, Piece,
+};
 use std::cmp::Reverse;
 use std::collections::HashMap;
 
@@ -40,18 +43,138 @@ impl<'a> Ordering<'a> {
         10_000 + victim_val * 10 - attacker_val
     }
 
+    /// Returns the square/piece of the cheapest attacker of `side` that can still hit
+    /// `target` given the (possibly already-thinned) occupancy `occ`, or `None` if `side`
+    /// has none left. Checked from pawns up to king so the result is always the least
+    /// valuable attacker.
+    fn least_valuable_attacker(b: &Board, occ: BitBoard, target: Square, side: Color) -> Option<(Square, Piece)> {
+        #[inline]
+        fn first_square(bb: BitBoard) -> Option<Square> { bb.into_iter().next() }
+
+        let side_occ = b.color_combined(side) & occ;
+
+        let pawns = get_pawn_attacks(target, opp(side), occ) & b.pieces(Piece::Pawn) & side_occ;
+        if let Some(sq) = first_square(pawns) { return Some((sq, Piece::Pawn)); }
+
+        let knights = get_knight_moves(target) & b.pieces(Piece::Knight) & side_occ;
+        if let Some(sq) = first_square(knights) { return Some((sq, Piece::Knight)); }
+
+        let bishops = get_bishop_moves(target, occ) & b.pieces(Piece::Bishop) & side_occ;
+        if let Some(sq) = first_square(bishops) { return Some((sq, Piece::Bishop)); }
+
+        let rooks = get_rook_moves(target, occ) & b.pieces(Piece::Rook) & side_occ;
+        if let Some(sq) = first_square(rooks) { return Some((sq, Piece::Rook)); }
+
+        let queens = (get_bishop_moves(target, occ) | get_rook_moves(target, occ)) & b.pieces(Piece::Queen) & side_occ;
+        if let Some(sq) = first_square(queens) { return Some((sq, Piece::Queen)); }
+
+        // The king can only recapture if the opponent has no attacker left on `target`
+        // afterwards -- otherwise it would be moving into (or, for an opposing king
+        // that's merely nearby rather than adjacent to `side`'s own king, illegally
+        // adjacent to) an attack. `attacks_square` checks the opponent's king too, but
+        // plainly -- it must not recurse back through this same king-legality guard, or
+        // two kings that both reach `target` without being adjacent to each other would
+        // send this call in circles.
+        if !Self::attacks_square(b, occ, target, opp(side)) {
+            let king = get_king_moves(target) & b.pieces(Piece::King) & side_occ;
+            if let Some(sq) = first_square(king) { return Some((sq, Piece::King)); }
+        }
+
+        None
+    }
+
+    /// Whether any piece of `side` (including its king, unconditionally) attacks
+    /// `target` given occupancy `occ`. Used only to decide whether the *other* side's
+    /// king may legally recapture on `target` -- see `least_valuable_attacker`.
+    fn attacks_square(b: &Board, occ: BitBoard, target: Square, side: Color) -> bool {
+        let side_occ = b.color_combined(side) & occ;
+        if (get_pawn_attacks(target, opp(side), occ) & b.pieces(Piece::Pawn) & side_occ).popcnt() > 0 { return true; }
+        if (get_knight_moves(target) & b.pieces(Piece::Knight) & side_occ).popcnt() > 0 { return true; }
+        if (get_bishop_moves(target, occ) & b.pieces(Piece::Bishop) & side_occ).popcnt() > 0 { return true; }
+        if (get_rook_moves(target, occ) & b.pieces(Piece::Rook) & side_occ).popcnt() > 0 { return true; }
+        if ((get_bishop_moves(target, occ) | get_rook_moves(target, occ)) & b.pieces(Piece::Queen) & side_occ).popcnt() > 0 { return true; }
+        if (get_king_moves(target) & b.pieces(Piece::King) & side_occ).popcnt() > 0 { return true; }
+        false
+    }
+
+    /// Static Exchange Evaluation: the net material swing on `mv.get_dest()` once every
+    /// attacker that can reach the square has traded off in least-valuable-first order.
+    /// Returns 0 for non-captures. Implements the classic swap-off algorithm (see e.g.
+    /// the Chess Programming Wiki's "SEE - The Swap Algorithm").
+    pub fn see(&self, b: &Board, mv: ChessMove) -> i32 {
+        if !self.is_capture(b, mv) { return 0; }
+
+        let target = mv.get_dest();
+        let mover_from = mv.get_source();
+
+        // En passant's captured pawn doesn't sit on `target`.
+        let ep_victim_sq = if b.piece_on(target).is_none() {
+            Some(Square::make_square(mover_from.get_rank(), target.get_file()))
+        } else {
+            None
+        };
+
+        let mut occ = *b.combined();
+        if let Some(sq) = ep_victim_sq { occ &= !BitBoard::from_square(sq); }
+        occ &= !BitBoard::from_square(mover_from);
+
+        let victim_val = match ep_victim_sq {
+            Some(sq) => b.piece_on(sq).map(piece_val).unwrap_or(P),
+            None => b.piece_on(target).map(piece_val).unwrap_or(P),
+        };
+
+        let mut gain = [0i32; 32];
+        // A promoting capture both wins the victim and upgrades the pawn immediately.
+        gain[0] = victim_val + mv.get_promotion().map(|p| piece_val(p) - P).unwrap_or(0);
+        // Value now sitting on `target`, i.e. what the *next* attacker would capture.
+        let mut on_square_val = mv.get_promotion().map(piece_val)
+            .unwrap_or_else(|| b.piece_on(mover_from).map(piece_val).unwrap_or(P));
+
+        let mut side = opp(b.side_to_move());
+        let mut d = 0usize;
+
+        while d < gain.len() - 1 {
+            let (sq, piece) = match Self::least_valuable_attacker(b, occ, target, side) {
+                Some(x) => x,
+                None => break,
+            };
+            d += 1;
+            gain[d] = on_square_val - gain[d - 1];
+            occ &= !BitBoard::from_square(sq);
+            on_square_val = piece_val(piece);
+            side = opp(side);
+        }
+
+        while d > 0 {
+            gain[d - 1] = -std::cmp::max(-gain[d - 1], gain[d]);
+            d -= 1;
+        }
+        gain[0]
+    }
+
+    /// `jitter` perturbs the ordering of otherwise-tied quiet moves by a few points --
+    /// `0` (the main Lazy SMP worker) leaves ordering untouched; other workers pass a
+    /// small per-worker seed so they don't all walk an identical move order off the same
+    /// shared TT. At most ±3 points: never enough to outrank a TT move or killer, but
+    /// enough to swap moves whose other scores differ by less than 6.
     pub fn ordered_moves(
         &self,
         b: &Board,
         tt_move: Option<ChessMove>,
         killers: (Option<ChessMove>, Option<ChessMove>),
+        jitter: u64,
     ) -> Vec<ChessMove> {
         let mut moves: Vec<ChessMove> = MoveGen::new_legal(b).collect();
         let us = b.side_to_move();
         moves.sort_by_key(|&m| {
             let mut k = 0i64;
             if let Some(tm) = tt_move { if m == tm { k += 10_000_000; } }
-            k += self.mvv_lva(b, m) as i64;
+            if self.is_capture(b, m) {
+                // Losing captures (SEE < 0) sink below quiets instead of keeping their
+                // MVV-LVA rank, so the engine stops trying bad trades before quiet moves.
+                let see_score = self.see(b, m);
+                k += if see_score >= 0 { self.mvv_lva(b, m) as i64 } else { see_score as i64 };
+            }
             if let Some(k1) = killers.0 { if m == k1 { k += 5_000_000; } }
             if let Some(k2) = killers.1 { if m == k2 { k += 5_000_000; } }
             if b.make_move_new(m).checkers().popcnt() > 0 { k += 1_000; }
@@ -59,6 +182,10 @@ impl<'a> Ordering<'a> {
                 k += *self.history.get(&(us, m.get_source(), m.get_dest(), piece_code(pc))).unwrap_or(&0) as i64;
             }
             if self.is_capture(b, m) { k += 1; }
+            if jitter != 0 {
+                let move_hash = (m.get_source().to_index() as u64) * 64 + m.get_dest().to_index() as u64;
+                k += ((move_hash ^ jitter) % 7) as i64 - 3;
+            }
             Reverse(k)
         });
         moves
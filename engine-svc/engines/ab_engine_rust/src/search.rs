@@ -1,13 +1,16 @@
 use std::collections::HashMap;
-use std::env;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 
 use chess::{Board, BoardStatus, ChessMove, MoveGen};
 
 use crate::types::*;
 use crate::eval::ClassicalEval;
+use crate::evalparams::EvalParams;
 use crate::ordering::{Ordering as MoveOrdering, Killers, History};
+use crate::tb::Tb;
+use crate::kpk::Kpk;
 use crate::tt::{TT, to_tt, from_tt};
 use crate::types::board_key;
 
@@ -43,56 +46,139 @@ fn mvv_lva_quick(b: &Board, mv: ChessMove) -> i32 {
 }
 // ---------------------------------------------------------------------------
 
+// How often (in nodes) to check the hard deadline; checking every node would make
+// Instant::now() a hot-path cost.
+const TIME_CHECK_NODE_MASK: u64 = 2047;
+
 pub struct Search {
     pub nodes: u64,
     pub stop: Arc<AtomicBool>,
-    pub tt: TT,
+    // Shared across Lazy SMP worker threads, so probes/stores must go through TT's
+    // lock-free &self API rather than requiring exclusive access here.
+    pub tt: Arc<TT>,
     pub killers: Killers,
     pub history: History,
     eval: ClassicalEval,
+    hard_deadline: Option<Instant>,
+    contempt: i32,
+    // `None` when no tables were loaded (`SYZYGY_PATH` unset), so probing is just a
+    // branch away rather than an `Option` check plus a dummy table everywhere.
+    tb: Option<Arc<Tb>>,
+    // Unlike `tb`, always present: the KPK bitbase is generated at startup rather than
+    // loaded from an optional external path, so there's no "not loaded" case to model.
+    kpk: Arc<Kpk>,
+    // Per-worker move-ordering perturbation for Lazy SMP (see `set_move_jitter`). `0`
+    // (the default) leaves move ordering untouched.
+    move_jitter: u64,
 }
 
 impl Search {
-    pub fn new(stop: Arc<AtomicBool>) -> Self {
-        let tt_mb = env::var("TT_MB").ok().and_then(|s| s.parse::<usize>().ok()).unwrap_or(128);
+    /// Single-threaded search: allocates its own table and bitbase.
+    pub fn new(stop: Arc<AtomicBool>, tt_mb: usize, contempt: i32, eval_params: EvalParams) -> Self {
+        Self::with_tt(stop, Arc::new(TT::new_from_mb(tt_mb)), contempt, eval_params, None, Arc::new(Kpk::generate()))
+    }
+
+    /// Lazy SMP search: shares `tt` (and, if loaded, `tb`/`kpk`) with every other worker
+    /// searching this root.
+    pub fn with_tt(
+        stop: Arc<AtomicBool>,
+        tt: Arc<TT>,
+        contempt: i32,
+        eval_params: EvalParams,
+        tb: Option<Arc<Tb>>,
+        kpk: Arc<Kpk>,
+    ) -> Self {
         Self {
             nodes: 0,
             stop,
-            tt: TT::new_from_mb(tt_mb),
+            tt,
             killers: HashMap::new(),
             history: HashMap::new(),
-            eval: ClassicalEval,
+            eval: ClassicalEval::new(eval_params),
+            hard_deadline: None,
+            contempt,
+            tb,
+            kpk,
+            move_jitter: 0,
         }
     }
 
     #[inline] pub fn on_new_iter(&mut self) {
         self.nodes = 0;
-        self.tt.age = self.tt.age.wrapping_add(1);
+        self.tt.age.fetch_add(1, Ordering::Relaxed);
     }
 
     #[inline] pub fn evaluate(&self, b: &Board) -> i32 { self.eval.eval(b) }
 
-    fn qsearch(&mut self, b: &Board, mut alpha: i32, beta: i32) -> i32 {
+    /// Sets the per-worker move-ordering perturbation described on the `move_jitter`
+    /// field. Lazy SMP workers call this once at startup with a small `worker_id`-derived
+    /// seed so they diverge from the main worker's move order instead of just its depth
+    /// schedule.
+    pub fn set_move_jitter(&mut self, jitter: u64) {
+        self.move_jitter = jitter;
+    }
+
+    /// Sets the point at which the search must abort mid-iteration. `None` disables the
+    /// hard cutoff (fixed-depth/no-clock searches still stop via `self.stop`).
+    pub fn set_hard_deadline(&mut self, deadline: Option<Instant>) {
+        self.hard_deadline = deadline;
+    }
+
+    #[inline]
+    fn check_time(&mut self) {
+        if self.nodes & TIME_CHECK_NODE_MASK != 0 { return; }
+        if let Some(dl) = self.hard_deadline {
+            if Instant::now() >= dl { self.stop.store(true, Ordering::Relaxed); }
+        }
+    }
+
+    /// Score of a theoretical draw from the side-to-move's perspective. `0` unless
+    /// `Contempt` is set, in which case the engine is steered away from (positive) or
+    /// towards (negative) repeating/fifty-move draws instead of evaluating them as flat 0.
+    #[inline] fn draw_score(&self) -> i32 { -self.contempt }
+
+    fn qsearch(&mut self, b: &Board, mut alpha: i32, beta: i32, ply: i32) -> i32 {
         if self.stop.load(Ordering::Relaxed) { return alpha; }
         self.nodes = self.nodes.wrapping_add(1);
+        self.check_time();
 
         match b.status() {
             BoardStatus::Checkmate => return -MATE,
-            BoardStatus::Stalemate => return 0,
+            BoardStatus::Stalemate => return self.draw_score(),
             BoardStatus::Ongoing => {}
         }
-        if insufficient_material(b) { return 0; }
-        if halfmove_clock_from_fen(b) as i32 >= 100 { return 0; }
+        if insufficient_material(b) { return self.draw_score(); }
+        if halfmove_clock_from_fen(b) as i32 >= 100 { return self.draw_score(); }
+
+        // Syzygy probe: qsearch runs deep into the tree, so it's often the first point
+        // a capture sequence drops into tablebase-covered material -- worth the same
+        // short-circuit negamax gets rather than grinding out a static eval instead.
+        if let Some(tb) = &self.tb {
+            if let Some(tb_score) = tb.probe_wdl(b, ply) {
+                return tb_score;
+            }
+        }
+
+        // KPK bitbase: covers the common king-and-pawn endgame exactly even when no
+        // Syzygy tables are loaded (or the material hasn't shrunk far enough for them yet).
+        if let Some(kpk_score) = self.kpk.probe(b, ply) {
+            return kpk_score;
+        }
 
         let stand = self.evaluate(b);
         if stand >= beta { return beta; }
         if stand > alpha { alpha = stand; }
         if stand + Q_FUTILITY_MARGIN < alpha { return alpha; }
 
-        // Build noisy list without borrowing self
+        // Build noisy list without borrowing self. Losing captures (SEE < 0) are dropped
+        // here rather than just sunk in move order -- qsearch has no move-count pruning
+        // to fall back on, so a bad trade would otherwise burn nodes exploring a line the
+        // search already knows loses material.
+        let see_of = MoveOrdering { history: &self.history };
         let mut noisy = Vec::new();
         for m in MoveGen::new_legal(b) {
             let cap = is_capture_quick(b, m);
+            if cap && see_of.see(b, m) < 0 { continue; }
             let promo = m.get_promotion().is_some();
             let gives_check = if Q_INCLUDE_CHECKS {
                 b.make_move_new(m).checkers().popcnt() > 0
@@ -106,13 +192,14 @@ impl Search {
         for m in noisy {
             if self.stop.load(Ordering::Relaxed) { break; }
             let nb = b.make_move_new(m);
-            let score = -self.qsearch(&nb, -beta, -alpha);
+            let score = -self.qsearch(&nb, -beta, -alpha, ply + 1);
             if score >= beta { return beta; }
             if score > alpha { alpha = score; }
         }
         alpha
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn negamax(
         &mut self,
         b: &Board,
@@ -126,24 +213,51 @@ impl Search {
     ) -> i32 {
         if self.stop.load(Ordering::Relaxed) { return alpha; }
         self.nodes = self.nodes.wrapping_add(1);
+        self.check_time();
 
         match b.status() {
             BoardStatus::Checkmate => return -MATE,
-            BoardStatus::Stalemate => return 0,
+            BoardStatus::Stalemate => return self.draw_score(),
             BoardStatus::Ongoing => {}
         }
-        if insufficient_material(b) { return 0; }
-        if halfmove_clock_from_fen(b) as i32 >= 100 { return 0; }
+        if insufficient_material(b) { return self.draw_score(); }
+        if halfmove_clock_from_fen(b) as i32 >= 100 { return self.draw_score(); }
 
         let k = board_key(b);
 
-        // Threefold: if current key already appears twice, this makes 3 -> draw
-        if rep_stack.iter().filter(|&&x| x == k).count() >= 2 { return 0; }
+        // Threefold: `rep_stack` is seeded with the game history since the last
+        // irreversible move, so a key that already appears twice here (once in the
+        // played game, once more in the search tree, or twice within the search tree
+        // alone) means this position is about to repeat for the third time. Checked
+        // before the TB/KPK probes below so an available repetition draw isn't overridden
+        // by a tablebase win/loss verdict for the position in isolation.
+        if rep_stack.iter().filter(|&&x| x == k).count() >= 2 { return self.draw_score(); }
         rep_stack.push(k);
 
+        // Syzygy probe: short-circuits the rest of this node with a perfect WDL verdict
+        // once the position is shallow enough (and castling-right-free) for the loaded
+        // tables to cover. Stored as EXACT so deeper re-visits via the TT skip the probe.
+        if ply > 0 {
+            if let Some(tb) = &self.tb {
+                if let Some(tb_score) = tb.probe_wdl(b, ply) {
+                    self.tt.store(k, MAX_AB_DEPTH, to_tt(tb_score, ply), EXACT, None);
+                    rep_stack.pop();
+                    return tb_score;
+                }
+            }
+
+            // KPK bitbase: same short-circuit as the Syzygy probe above, for the one
+            // endgame this engine can classify perfectly without any external tables.
+            if let Some(kpk_score) = self.kpk.probe(b, ply) {
+                self.tt.store(k, MAX_AB_DEPTH, to_tt(kpk_score, ply), EXACT, None);
+                rep_stack.pop();
+                return kpk_score;
+            }
+        }
+
         // TT probe
         if let Some(tte) = self.tt.probe(k) {
-            if tte.depth as i32 >= depth {
+            if tte.depth >= depth {
                 let tt_score = from_tt(tte.score, ply);
                 if tte.flag == EXACT { rep_stack.pop(); return tt_score; }
                 if tte.flag == ALPHA && tt_score <= alpha { rep_stack.pop(); return tt_score; }
@@ -154,7 +268,7 @@ impl Search {
         let in_check = b.checkers().popcnt() > 0;
         let local_depth = if in_check { depth + 1 } else { depth };
         if local_depth <= 0 {
-            let rv = self.qsearch(b, alpha, beta);
+            let rv = self.qsearch(b, alpha, beta, ply);
             rep_stack.pop();
             return rv;
         }
@@ -173,7 +287,7 @@ impl Search {
         // Order moves in a short scope so &self.history doesn't overlap with &mut self below
         let moves = {
             let ord = MoveOrdering { history: &self.history };
-            ord.ordered_moves(b, tt_move, killers)
+            ord.ordered_moves(b, tt_move, killers, self.move_jitter)
         };
 
         // Refined endgame check: hard material threshold OR low phase
@@ -188,11 +302,10 @@ impl Search {
             let gives_chk = nb.checkers().popcnt() > 0;
 
             // Frontier futility (disabled in endgames/PV/improving)
-            if !endgame_like && !is_pv && !improving && local_depth == 1 && !is_cap && !gives_chk {
-                if node_eval + (FUTILITY_MARGIN_BASE / 2) <= alpha {
-                    move_index += 1;
-                    continue;
-                }
+            if !endgame_like && !is_pv && !improving && local_depth == 1 && !is_cap && !gives_chk
+                && node_eval + (FUTILITY_MARGIN_BASE / 2) <= alpha {
+                move_index += 1;
+                continue;
             }
 
             // Move-count pruning (disabled in endgames/PV/improving/near-root)
@@ -259,7 +372,7 @@ impl Search {
         // No legal moves
         if best_move.is_none() && MoveGen::new_legal(b).next().is_none() {
             rep_stack.pop();
-            return if in_check { -MATE } else { 0 };
+            return if in_check { -MATE } else { self.draw_score() };
         }
 
         let flag = if best_score <= orig_alpha { ALPHA }
@@ -298,6 +411,7 @@ pub fn root_search(
     depth: i32,
     alpha: i32,
     beta: i32,
+    game_history: &[u64],
 ) -> (Option<ChessMove>, i32) {
     let mut a = alpha;
     let mut best_score = -INF;
@@ -309,7 +423,7 @@ pub fn root_search(
     // Build ordered moves in a short scope so &search.history doesn't overlap with &mut search
     let mut moves = {
         let ord = MoveOrdering { history: &search.history };
-        ord.ordered_moves(b, tt_move, killers)
+        ord.ordered_moves(b, tt_move, killers, search.move_jitter)
     };
 
     let parent_eval = Some(search.evaluate(b));
@@ -318,7 +432,10 @@ pub fn root_search(
         if search.stop.load(Ordering::Relaxed) { break; }
         let nb = b.make_move_new(m);
 
-        let mut rep_stack = vec![board_key(b)];
+        // Seeded with the game history (since the last irreversible move) rather than
+        // just the root key, so threefold repetition is caught across the board/moves
+        // already played, not only within this search tree.
+        let mut rep_stack = game_history.to_vec();
         let mut score;
         if i == 0 {
             score = -search.negamax(&nb, depth - 1, -beta, -a, 1, true, parent_eval, &mut rep_stack);
@@ -0,0 +1,110 @@
+// ab_engine_rust/src/tb.rs
+//
+// Syzygy endgame tablebase probing via `shakmaty-syzygy`, loaded once at startup from
+// `SYZYGY_PATH` (a `:`-separated list of directories, like `PATH`). `chess`'s `Board` has
+// no common type with `shakmaty`'s `Chess`, so probes round-trip through FEN -- fine since
+// they only fire near the tablebase's piece-count ceiling, not on every node.
+
+use chess::{Board, ChessMove, MoveGen};
+use shakmaty::fen::Fen;
+use shakmaty::{CastlingMode, Chess};
+use shakmaty_syzygy::{Tablebase, Wdl};
+
+use crate::types::{has_castle_rights, total_piece_count, MATE};
+
+// Kept well clear of `MATE` so a genuine forced mate the search finds always outranks a
+// merely-won tablebase position at the same ply.
+const TB_WIN: i32 = MATE - 1000;
+
+pub struct Tb {
+    table: Tablebase<Chess>,
+    max_pieces: u32,
+}
+
+impl Tb {
+    /// Loads every table reachable from `SYZYGY_PATH`. Returns `None` if the env var is
+    /// unset or no table could be loaded, so callers can skip probing entirely instead of
+    /// carrying a table with a zero piece ceiling.
+    pub fn load_from_env() -> Option<Self> {
+        let path = std::env::var("SYZYGY_PATH").ok()?;
+        let mut table = Tablebase::new();
+        for dir in path.split(':') {
+            if dir.is_empty() { continue; }
+            // Returns a count of table files, not a piece count; the ceiling is read below.
+            let _ = table.add_directory(dir);
+        }
+        let max_pieces = table.max_pieces() as u32;
+        if max_pieces == 0 { None } else { Some(Self { table, max_pieces }) }
+    }
+
+    /// Whether `b` is shallow enough (piece count, no castling rights left) for the loaded
+    /// tables to cover.
+    #[inline]
+    pub fn covers(&self, b: &Board) -> bool {
+        total_piece_count(b) as u32 <= self.max_pieces && !has_castle_rights(b)
+    }
+
+    fn to_shakmaty(b: &Board) -> Option<Chess> {
+        let fen: Fen = b.to_string().parse().ok()?;
+        fen.into_position(CastlingMode::Standard).ok()
+    }
+
+    /// WDL probe from the side-to-move's perspective, already offset into the
+    /// win/loss/draw score the search expects; `None` if the position isn't coverable or
+    /// the probe fails (e.g. a table for this material signature wasn't loaded).
+    pub fn probe_wdl(&self, b: &Board, ply: i32) -> Option<i32> {
+        if !self.covers(b) { return None; }
+        let pos = Self::to_shakmaty(b)?;
+        // `probe_wdl` also consults the halfmove clock and can come back ambiguous right
+        // at the 50-move boundary; `after_zeroing` collapses that back to the plain `Wdl`
+        // this function has always returned, at the cost of assuming play continues from
+        // a zeroing move -- true for how these probes are actually used (root/search nodes
+        // just reached by a move, not resumed mid-ply).
+        let wdl = self.table.probe_wdl(&pos).ok()?.after_zeroing();
+        Some(match wdl {
+            Wdl::Win | Wdl::CursedWin => TB_WIN - ply,
+            Wdl::Loss | Wdl::BlessedLoss => -TB_WIN + ply,
+            Wdl::Draw => 0,
+        })
+    }
+
+    /// Root move selection via DTZ: among the legal moves, picks one that preserves the
+    /// current position's WDL verdict while minimizing distance-to-zeroing, so converting
+    /// a won endgame doesn't let the fifty-move counter turn it into a draw.
+    pub fn probe_root_move(&self, b: &Board) -> Option<ChessMove> {
+        if !self.covers(b) { return None; }
+
+        let mut best: Option<(ChessMove, i32, i32)> = None; // (move, wdl_for_us, dtz_abs)
+        for m in MoveGen::new_legal(b) {
+            let nb = b.make_move_new(m);
+            let pos = match Self::to_shakmaty(&nb) {
+                Some(p) => p,
+                None => continue,
+            };
+            let wdl = match self.table.probe_wdl(&pos) {
+                Ok(w) => w.after_zeroing(),
+                Err(_) => continue,
+            };
+            // `wdl` is from the *opponent's* perspective after our move, so a loss for
+            // them is the outcome we're after.
+            let wdl_for_us = match wdl {
+                Wdl::Loss => 2,
+                Wdl::BlessedLoss => 1,
+                Wdl::Draw => 0,
+                Wdl::CursedWin => -1,
+                Wdl::Win => -2,
+            };
+            // `probe_dtz` may return a rounded value for tables that don't store exact
+            // DTZ; this only ranks moves against each other, so the rounding doesn't
+            // matter and we just unwrap to the plain `Dtz`.
+            let dtz_abs = self.table.probe_dtz(&pos).map(|d| d.ignore_rounding().0.abs()).unwrap_or(i32::MAX);
+
+            let better = match &best {
+                None => true,
+                Some((_, bw, bd)) => (wdl_for_us, -dtz_abs) > (*bw, -*bd),
+            };
+            if better { best = Some((m, wdl_for_us, dtz_abs)); }
+        }
+        best.map(|(m, _, _)| m)
+    }
+}
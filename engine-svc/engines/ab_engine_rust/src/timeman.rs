@@ -0,0 +1,33 @@
+// ab_engine_rust/src/timeman.rs
+//
+// Time allocation for UCI clock-based `go` commands (wtime/btime/winc/binc/movestogo).
+
+use std::time::Duration;
+
+// Hard cap is a fraction of the remaining clock, so a single move can never eat the
+// whole game even if movestogo is wildly optimistic.
+const HARD_CAP_FRACTION_NUM: u64 = 1;
+const HARD_CAP_FRACTION_DEN: u64 = 2;
+
+// Used when the GUI doesn't send `movestogo` (i.e. no fixed time control).
+const DEFAULT_MOVES_TO_GO: u64 = 30;
+
+pub struct TimeBudget {
+    pub soft: Duration,
+    pub hard: Duration,
+}
+
+/// Computes a soft budget (iterative deepening should not *start* a new depth once this
+/// elapses) and a hard budget (the search must abort mid-iteration by this point), given
+/// the side to move's remaining clock/increment in milliseconds.
+pub fn allocate(remaining_ms: u64, inc_ms: u64, movestogo: Option<u32>, move_overhead_ms: u64) -> TimeBudget {
+    let mtg = movestogo.map(|m| m as u64).unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+
+    let hard_cap_ms = (remaining_ms * HARD_CAP_FRACTION_NUM / HARD_CAP_FRACTION_DEN)
+        .saturating_sub(move_overhead_ms)
+        .max(1);
+
+    let soft_ms = (remaining_ms / mtg + inc_ms * 3 / 4).min(hard_cap_ms);
+
+    TimeBudget { soft: Duration::from_millis(soft_ms), hard: Duration::from_millis(hard_cap_ms) }
+}
@@ -1,32 +1,67 @@
 use chess::ChessMove;
-use crate::types::{pack_move, unpack_move, EXACT, ALPHA, BETA, MAX_AB_DEPTH, MATE};
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+
+use crate::types::{pack_move, MAX_AB_DEPTH, MATE};
 
 #[derive(Clone, Copy)]
 pub struct TTEntry {
     pub key: u64,
-    pub depth: i16,
+    pub depth: i32,
     pub score: i32,
     pub flag: i8,
     pub age: u8,
     pub best: u16,
 }
-impl Default for TTEntry {
+
+const TT_ASSOC: usize = 4;
+
+// --- Packing: everything but the key lives in one u64 "data" word ---
+// [0..32)  score  (i32 bits)
+// [32..39) depth  (0..=127, MAX_AB_DEPTH comfortably fits)
+// [39..41) flag   (EXACT/ALPHA/BETA, offset by +1 to stay unsigned)
+// [41..47) age    (wraps mod 64; only used to break replacement ties)
+// [47..63) best   (packed move)
+fn pack_entry(depth: i32, score: i32, flag: i8, age: u8, best: u16) -> u64 {
+    let score_bits = (score as u32) as u64;
+    let depth_bits = (depth.clamp(0, 127) as u64) & 0x7F;
+    let flag_bits = ((flag + 1) as u64) & 0x3;
+    let age_bits = (age as u64) & 0x3F;
+    let best_bits = best as u64;
+    score_bits | (depth_bits << 32) | (flag_bits << 39) | (age_bits << 41) | (best_bits << 47)
+}
+
+fn unpack_entry(key: u64, data: u64) -> TTEntry {
+    let score = (data & 0xFFFF_FFFF) as u32 as i32;
+    let depth = ((data >> 32) & 0x7F) as i32;
+    let flag = (((data >> 39) & 0x3) as i8) - 1;
+    let age = ((data >> 41) & 0x3F) as u8;
+    let best = ((data >> 47) & 0xFFFF) as u16;
+    TTEntry { key, depth, score, flag, age, best }
+}
+
+struct TTSlot {
+    // Hyatt's lockless XOR trick: this word stores `key ^ data`, never the raw key. A
+    // torn read (one thread reading while another stores) makes `key_xor_data ^ data`
+    // come out wrong, which is indistinguishable from a miss and simply gets skipped --
+    // no mutex needed on the hot path.
+    key_xor_data: AtomicU64,
+    data: AtomicU64,
+}
+impl Default for TTSlot {
     fn default() -> Self {
-        Self { key: 0, depth: -32768, score: 0, flag: EXACT, age: 0, best: 0 }
+        Self { key_xor_data: AtomicU64::new(0), data: AtomicU64::new(0) }
     }
 }
 
-const TT_ASSOC: usize = 4;
-
 pub struct TT {
-    buckets: Vec<[TTEntry; TT_ASSOC]>,
+    slots: Vec<TTSlot>,
     mask: usize,
-    pub age: u8,
+    pub age: AtomicU8,
 }
 impl TT {
     pub fn new_from_mb(tt_mb: usize) -> Self {
         use std::mem::size_of;
-        let entry_sz = size_of::<TTEntry>().max(1);
+        let entry_sz = size_of::<u64>() * 2;
         let bytes = tt_mb.saturating_mul(1024 * 1024);
         let total_entries = (bytes / entry_sz).max(TT_ASSOC);
         let mut buckets = (total_entries / TT_ASSOC).max(1);
@@ -36,47 +71,66 @@ impl TT {
         buckets = pow2;
 
         let mask = buckets - 1;
-        let mut vec = Vec::with_capacity(buckets);
-        vec.resize(buckets, [TTEntry::default(); TT_ASSOC]);
-        Self { buckets: vec, mask, age: 0 }
+        let mut slots = Vec::with_capacity(buckets * TT_ASSOC);
+        slots.resize_with(buckets * TT_ASSOC, TTSlot::default);
+        Self { slots, mask, age: AtomicU8::new(0) }
     }
-    #[inline] fn idx(&self, key: u64) -> usize { (key as usize) & self.mask }
+    #[inline] fn bucket_start(&self, key: u64) -> usize { ((key as usize) & self.mask) * TT_ASSOC }
 
+    /// Lock-free probe: safe to call from any number of worker threads concurrently.
     pub fn probe(&self, key: u64) -> Option<TTEntry> {
-        let bucket = &self.buckets[self.idx(key)];
+        let start = self.bucket_start(key);
         let mut best: Option<TTEntry> = None;
-        for &e in bucket.iter() {
-            if e.key == key && e.depth > -32768 {
-                if best.map_or(true, |b| e.depth > b.depth) { best = Some(e); }
-            }
+        for slot in &self.slots[start..start + TT_ASSOC] {
+            let data = slot.data.load(Ordering::Relaxed);
+            if data == 0 { continue; }
+            let kx = slot.key_xor_data.load(Ordering::Relaxed);
+            if kx ^ data != key { continue; } // torn read or genuine miss -- either way, skip
+            let e = unpack_entry(key, data);
+            if best.is_none_or(|b| e.depth > b.depth) { best = Some(e); }
         }
         best
     }
 
-    pub fn store(&mut self, key: u64, depth: i32, score: i32, flag: i8, best: Option<ChessMove>) {
-        let i = self.idx(key);
-        let bucket = &mut self.buckets[i];
+    /// Lock-free store: each worker writes its own slot choice independently. Two
+    /// workers racing for the same slot can clobber each other's entry, which is a
+    /// correctness-preserving performance tradeoff Lazy SMP always makes in exchange for
+    /// not serializing on a mutex.
+    pub fn store(&self, key: u64, depth: i32, score: i32, flag: i8, best: Option<ChessMove>) {
+        let start = self.bucket_start(key);
+        let bucket = &self.slots[start..start + TT_ASSOC];
+        let age = self.age.load(Ordering::Relaxed);
+        let data = pack_entry(depth, score, flag, age, best.map(pack_move).unwrap_or(0));
 
-        for e in bucket.iter_mut() {
-            if e.key == key {
-                *e = TTEntry { key, depth: depth as i16, score, flag, age: self.age,
-                               best: best.map(pack_move).unwrap_or(0) };
-                return;
+        // Prefer overwriting an existing entry for this key, then the shallowest/oldest
+        // slot in the bucket.
+        let mut replace_at = 0usize;
+        let mut replace_is_match = false;
+        let mut replace_depth = i32::MAX;
+        let mut replace_age_gap = 0u8;
+        for (j, slot) in bucket.iter().enumerate() {
+            let slot_data = slot.data.load(Ordering::Relaxed);
+            let slot_kx = slot.key_xor_data.load(Ordering::Relaxed);
+            let is_match = slot_data != 0 && slot_kx ^ slot_data == key;
+            if is_match {
+                replace_at = j;
+                replace_is_match = true;
+                break;
+            }
+            let e = unpack_entry(key, slot_data);
+            let age_gap = age.wrapping_sub(e.age);
+            let worse = e.depth < replace_depth || (e.depth == replace_depth && age_gap > replace_age_gap);
+            if j == 0 || worse {
+                replace_at = j;
+                replace_depth = e.depth;
+                replace_age_gap = age_gap;
             }
         }
+        let _ = replace_is_match;
 
-        // Prefer evicting shallower, then *older* on tie (quality > speed).
-        let mut replace_at = 0usize;
-        for (j, e) in bucket.iter().enumerate() {
-            let r = &bucket[replace_at];
-            let worse_depth = e.depth < r.depth;
-            let same_depth_older = e.depth == r.depth && r.age.wrapping_sub(e.age) > 0;
-            if worse_depth || same_depth_older { replace_at = j; }
-        }
-        bucket[replace_at] = TTEntry {
-            key, depth: depth as i16, score, flag, age: self.age,
-            best: best.map(pack_move).unwrap_or(0),
-        };
+        let slot = &bucket[replace_at];
+        slot.data.store(data, Ordering::Relaxed);
+        slot.key_xor_data.store(key ^ data, Ordering::Relaxed);
     }
 }
 
@@ -1,4 +1,4 @@
-use chess::{BitBoard, Board, ChessMove, Color, Piece, Square};
+use chess::{Board, ChessMove, Color, Piece, Square};
 
 // ---------------------------
 // Tunables / constants
@@ -48,13 +48,126 @@ pub const ROOK_SEMIOPEN_FILE_BONUS: i32 = 6;
 pub const DOUBLED_PAWN_PENALTY_MG: i32 = 10;
 pub const ISOLATED_PAWN_PENALTY_MG: i32 = 8;
 
-// PSTs (kept zeroed for brevity)
-pub const PST_PAWN: [i32; 64] = [0; 64];
-pub const PST_KNIGHT: [i32; 64] = [0; 64];
-pub const PST_BISHOP: [i32; 64] = [0; 64];
-pub const PST_ROOK: [i32; 64] = [0; 64];
-pub const PST_QUEEN: [i32; 64] = [0; 64];
-pub const PST_KING: [i32; 64] = [0; 64];
+// --- Piece-square tables, indexed a1..h8 (rank-major, so index 0 is White's a1) ---
+// Middlegame and endgame variants, tapered together in `ClassicalEval` via `game_phase`.
+// Values are the well-known PeSTO tables (White's point of view; `pst_index_for` mirrors
+// Black via the xor-56 trick instead of a second set of constants), except
+// `PST_KING_EG` below, which is a symmetric king-centralization table.
+pub const PST_PAWN: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,  0,   0,
+    -35,  -1, -20, -23, -15,  24, 38, -22,
+    -26,  -4,  -4, -10,   3,   3, 33, -12,
+    -27,  -2,  -5,  12,  17,   6, 10, -25,
+    -14,  13,   6,  21,  23,  12, 17, -23,
+     -6,   7,  26,  31,  65,  56, 25, -20,
+     98, 134,  61,  95,  68, 126, 34, -11,
+      0,   0,   0,   0,   0,   0,  0,   0,
+];
+pub const PST_PAWN_EG: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     13,   8,   8,  10,  13,   0,   2,  -7,
+      4,   7,  -6,   1,   0,  -5,  -1,  -8,
+     13,   9,  -3,  -7,  -7,  -8,   3,  -1,
+     32,  24,  13,   5,  -2,   4,  17,  17,
+     94, 100,  85,  67,  56,  53,  82,  84,
+    178, 173, 158, 134, 147, 132, 165, 187,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+pub const PST_KNIGHT: [i32; 64] = [
+    -105, -21, -58, -33, -17, -28, -19,  -23,
+     -29, -53, -12,  -3,  -1,  18, -14,  -19,
+     -23,  -9,  12,  10,  19,  17,  25,  -16,
+     -13,   4,  16,  13,  28,  19,  21,   -8,
+      -9,  17,  19,  53,  37,  69,  18,   22,
+     -47,  60,  37,  65,  84, 129,  73,   44,
+     -73, -41,  72,  36,  23,  62,   7,  -17,
+    -167, -89, -34, -49,  61, -97, -15, -107,
+];
+pub const PST_KNIGHT_EG: [i32; 64] = [
+    -29, -51, -23, -15, -22, -18, -50, -64,
+    -42, -20, -10,  -5,  -2, -20, -23, -44,
+    -23,  -3,  -1,  15,  10,  -3, -20, -22,
+    -18,  -6,  16,  25,  16,  17,   4, -18,
+    -17,   3,  22,  22,  22,  11,   8, -18,
+    -24, -20,  10,   9,  -1,  -9, -19, -41,
+    -25,  -8, -25,  -2,  -9, -25, -24, -52,
+    -58, -38, -13, -28, -31, -27, -63, -99,
+];
+
+pub const PST_BISHOP: [i32; 64] = [
+    -33,  -3, -14, -21, -13, -12, -39, -21,
+      4,  15,  16,   0,   7,  21,  33,   1,
+      0,  15,  15,  15,  14,  27,  18,  10,
+     -6,  13,  13,  26,  34,  12,  10,   4,
+     -4,   5,  19,  50,  37,  37,   7,  -2,
+    -16,  37,  43,  40,  35,  50,  37,  -2,
+    -26,  16, -18, -13,  30,  59,  18, -47,
+    -29,   4, -82, -37, -25, -42,   7,  -8,
+];
+pub const PST_BISHOP_EG: [i32; 64] = [
+    -23,  -9, -23,  -5, -9, -16,  -5, -17,
+    -14, -18,  -7,  -1,  4,  -9, -15, -27,
+    -12,  -3,   8,  10, 13,   3,  -7, -15,
+     -6,   3,  13,  19,  7,  10,  -3,  -9,
+     -3,   9,  12,   9, 14,  10,   3,   2,
+      2,  -8,   0,  -1, -2,   6,   0,   4,
+     -8,  -4,   7, -12, -3, -13,  -4, -14,
+    -14, -21, -11,  -8, -7,  -9, -17, -24,
+];
+
+pub const PST_ROOK: [i32; 64] = [
+    -19, -13,   1,  17,  16,   7, -37, -26,
+    -44, -16, -20,  -9,  -1,  11,  -6, -71,
+    -45, -25, -16, -17,   3,   0,  -5, -33,
+    -36, -26, -12,  -1,   9,  -7,   6, -23,
+    -24, -11,   7,  26,  24,  35,  -8, -20,
+     -5,  19,  26,  36,  17,  45,  61,  16,
+     27,  32,  58,  62,  80,  67,  26,  44,
+     32,  42,  32,  51,  63,   9,  31,  43,
+];
+pub const PST_ROOK_EG: [i32; 64] = [
+     -9,   2,   3,  -1,  -5, -13,   4, -20,
+     -6,  -6,   0,   2,  -9,  -9, -11,  -3,
+     -4,   0,  -5,  -1,  -7, -12,  -8, -16,
+      3,   5,   8,   4,  -5,  -6,  -8, -11,
+      4,   3,  13,   1,   2,   1,  -1,   2,
+      7,   7,   7,   5,   4,  -3,  -5,  -3,
+     11,  13,  13,  11,  -3,   3,   8,   3,
+     13,  10,  18,  15,  12,  12,   8,   5,
+];
+
+pub const PST_QUEEN: [i32; 64] = [
+     -1, -18,  -9,  10, -15, -25, -31, -50,
+    -35,  -8,  11,   2,   8,  15,  -3,   1,
+    -14,   2, -11,  -2,  -5,   2,  14,   5,
+     -9, -26,  -9, -10,  -2,  -4,   3,  -3,
+    -27, -27, -16, -16,  -1,  17,  -2,   1,
+    -13, -17,   7,   8,  29,  56,  47,  57,
+    -24, -39,  -5,   1, -16,  57,  28,  54,
+    -28,   0,  29,  12,  59,  44,  43,  45,
+];
+pub const PST_QUEEN_EG: [i32; 64] = [
+    -33, -28, -22, -43,  -5, -32, -20, -41,
+    -22, -23, -30, -16, -16, -23, -36, -32,
+    -16, -27,  15,   6,   9,  17,  10,   5,
+    -18,  28,  19,  47,  31,  34,  39,  23,
+      3,  22,  24,  45,  57,  40,  57,  36,
+    -20,   6,   9,  49,  47,  35,  19,   9,
+    -17,  20,  32,  41,  58,  25,  30,   0,
+     -9,  22,  22,  27,  27,  19,  10,  20,
+];
+
+pub const PST_KING: [i32; 64] = [
+    -15,  36,  12, -54,   8, -28,  24,  14,
+      1,   7,  -8, -64, -43, -16,   9,   8,
+    -14, -14, -22, -46, -44, -30, -15, -27,
+    -49,  -1, -27, -39, -46, -44, -33, -51,
+    -17, -20, -12, -27, -30, -25, -14, -36,
+     -9,  24,   2, -16, -20,   6,  22, -22,
+     29,  -1, -20,  -7,  -8,  -4, -38, -29,
+    -65,  23,  16, -15, -56, -34,   2,  13,
+];
 
 // --- Endgame king PSQT ---
 pub const PST_KING_EG: [i32; 64] = [
@@ -106,8 +219,18 @@ pub fn game_phase(b: &Board) -> i32 {
         Piece::King => PST_KING[idx],
     }
 }
+#[inline] pub fn pst_eg_for(piece: Piece, idx: usize) -> i32 {
+    match piece {
+        Piece::Pawn => PST_PAWN_EG[idx],
+        Piece::Knight => PST_KNIGHT_EG[idx],
+        Piece::Bishop => PST_BISHOP_EG[idx],
+        Piece::Rook => PST_ROOK_EG[idx],
+        Piece::Queen => PST_QUEEN_EG[idx],
+        Piece::King => PST_KING_EG[idx],
+    }
+}
 #[inline] pub fn pst_index_for(color: Color, sq: Square) -> usize {
-    let i = sq.to_index() as usize;
+    let i = sq.to_index();
     if color == Color::White { i } else { i ^ 56 }
 }
 
@@ -145,6 +268,13 @@ pub fn fullmove_number_from_fen(b: &Board) -> u32 {
     fen.split_whitespace().nth(5).and_then(|s| s.parse::<u32>().ok()).unwrap_or(1)
 }
 
+/// `true` if either side still has a castling right. Syzygy tables only cover positions
+/// with none left, so this gates tablebase probing.
+#[inline]
+pub fn has_castle_rights(b: &Board) -> bool {
+    b.to_string().split_whitespace().nth(2).is_some_and(|s| s != "-")
+}
+
 pub fn count_pieces(b: &Board, piece: Piece, color: Color) -> i32 {
     (b.pieces(piece) & b.color_combined(color)).popcnt() as i32
 }
@@ -192,7 +322,7 @@ pub fn total_material_excl_kings(b: &Board) -> i32 {
 
 // Rook file bonus: open = no pawns for either side on the file; semi-open = no own pawn but some enemy pawn.
 #[inline]
-pub fn rook_file_bonus(b: &Board, c: Color, sq: Square) -> i32 {
+pub fn rook_file_bonus(b: &Board, c: Color, sq: Square, open_bonus: i32, semi_open_bonus: i32) -> i32 {
     let f = file_idx(sq);
     let our_pawns = b.color_combined(c) & b.pieces(Piece::Pawn);
     let their_pawns = b.color_combined(opp(c)) & b.pieces(Piece::Pawn);
@@ -204,7 +334,7 @@ pub fn rook_file_bonus(b: &Board, c: Color, sq: Square) -> i32 {
     }
     let mut opp_on_file = false;
     for ps in their_pawns { if file_idx(ps) == f { opp_on_file = true; break; } }
-    if opp_on_file { ROOK_SEMIOPEN_FILE_BONUS } else { ROOK_OPEN_FILE_BONUS }
+    if opp_on_file { semi_open_bonus } else { open_bonus }
 }
 
 // Very light & cheap structure tests (MG-only usage)
@@ -218,7 +348,7 @@ pub fn is_doubled_pawn_on_file(b: &Board, c: Color, file: i32) -> bool {
 }
 pub fn is_isolated_pawn(b: &Board, c: Color, file: i32) -> bool {
     let has_on = |ff: i32| -> bool {
-        if ff < 0 || ff > 7 { return false; }
+        if !(0..=7).contains(&ff) { return false; }
         let pawns = b.color_combined(c) & b.pieces(Piece::Pawn);
         for ps in pawns { if file_idx(ps) == ff { return true; } }
         false
@@ -234,9 +364,9 @@ pub fn is_passed_pawn(b: &Board, sq: Square, us: Color) -> bool {
     let f = file_idx(sq);
     for df in -1..=1 {
         let ff = f + df;
-        if ff < 0 || ff > 7 { continue; }
+        if !(0..=7).contains(&ff) { continue; }
         for rr in (our_rank + 1)..=6 {
-            let idx = if us == Color::White { (rr * 8 + ff) } else { ((7 - rr) * 8 + ff) };
+            let idx = if us == Color::White { rr * 8 + ff } else { (7 - rr) * 8 + ff };
             let sq2 = unsafe { Square::new(idx as u8) };
             if b.piece_on(sq2) == Some(Piece::Pawn) && b.color_on(sq2) == Some(them) {
                 return false;